@@ -82,6 +82,87 @@ pub struct UpdateEvent {
     pub content: Option<String>,
 }
 
+/// Event descriptor fields of an oracle asset pair overridden by an event.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+pub struct EventDescriptorOverrides {
+    pub base: Option<u16>,
+    pub is_signed: Option<bool>,
+    pub unit: Option<String>,
+    pub precision: Option<i32>,
+    pub num_digits: Option<u16>,
+}
+
+/// Body of `POST /v1/events`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CreateEventRequest {
+    pub uuid: String,
+    /// RFC3339 maturation time of the event
+    pub maturation: String,
+    /// asset pair of the event, optional when the oracle has a single asset pair
+    pub asset_pair: Option<String>,
+    /// overrides of the asset pair's event descriptor, for digit decomposition events
+    pub event_descriptor: Option<EventDescriptorOverrides>,
+    /// outcomes of an enum event, instead of a digit decomposition event
+    pub outcomes: Option<Vec<String>>,
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    pub idempotency_key: Option<String>,
+}
+
+/// Outcome to attest to, a number for digit decomposition events and a string for enum events.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum AttestationOutcome {
+    Numeric(i64),
+    Enum(String),
+}
+
+impl fmt::Display for AttestationOutcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AttestationOutcome::Numeric(outcome) => write!(f, "{}", outcome),
+            AttestationOutcome::Enum(outcome) => write!(f, "{}", outcome),
+        }
+    }
+}
+
+/// Body of `POST /v1/events/{uuid}/attestation`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct AttestationRequest {
+    pub asset_pair: Option<String>,
+    /// outcome to attest to, the price reported by the oracle's pricefeeds if `None`
+    pub outcome: Option<AttestationOutcome>,
+    /// reason for attesting before maturation, required to do so
+    pub early_attestation_reason: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+/// An attestation of `POST /v1/batch/attestations`, see [`AttestationRequest`].
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BatchAttestation {
+    pub uuid: String,
+    pub asset_pair: Option<String>,
+    pub outcome: Option<AttestationOutcome>,
+    pub early_attestation_reason: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+/// Body of `POST /v1/batch/events`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BatchCreateEventsRequest {
+    pub events: Vec<CreateEventRequest>,
+}
+
+/// Body of `POST /v1/batch/attestations`.
+#[derive(serde::Serialize, serde::Deserialize, Debug, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BatchAttestationRequest {
+    pub attestations: Vec<BatchAttestation>,
+}
+
 pub struct WalletBackendClient {
     client: Client,
     host: String,
@@ -136,6 +217,7 @@ impl WalletBackendClient {
 pub struct OracleBackendClient {
     client: Client,
     host: String,
+    api_key: Option<String>,
 }
 
 impl Default for OracleBackendClient {
@@ -155,9 +237,100 @@ impl OracleBackendClient {
         Self {
             client: Client::new(),
             host: host,
+            api_key: None,
+        }
+    }
+
+    /// Authenticates the requests creating and attesting events with `api_key`.
+    pub fn with_api_key(mut self, api_key: String) -> Self {
+        self.api_key = Some(api_key);
+        self
+    }
+
+    fn authorized_get(&self, url: Url) -> reqwest::RequestBuilder {
+        let request = self.client.get(url);
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
+        }
+    }
+
+    fn authorized_post(&self, url: Url) -> reqwest::RequestBuilder {
+        let request = self.client.post(url);
+        match &self.api_key {
+            Some(api_key) => request.bearer_auth(api_key),
+            None => request,
         }
     }
 
+    /// Creates an event with `POST /v1/events`.
+    pub async fn post_event(&self, request: &CreateEventRequest) -> Result<ApiResult, Error> {
+        let uri = format!("{}/v1/events", String::as_str(&self.host.clone()));
+        let url = Url::parse(uri.as_str()).unwrap();
+        let res = self.authorized_post(url).json(request).send().await?;
+        let result = ApiResult {
+            status: res.status().as_u16(),
+            response: res,
+        };
+        Ok(result)
+    }
+
+    /// Attests an event with `POST /v1/events/{uuid}/attestation`.
+    pub async fn post_attestation(
+        &self,
+        uuid: String,
+        request: &AttestationRequest,
+    ) -> Result<ApiResult, Error> {
+        let uri = format!(
+            "{}/v1/events/{}/attestation",
+            String::as_str(&self.host.clone()),
+            uuid.as_str()
+        );
+        let url = Url::parse(uri.as_str()).unwrap();
+        let res = self.authorized_post(url).json(request).send().await?;
+        let result = ApiResult {
+            status: res.status().as_u16(),
+            response: res,
+        };
+        Ok(result)
+    }
+
+    /// Creates many events with `POST /v1/batch/events`, the response holding the result of each.
+    pub async fn post_events(
+        &self,
+        request: &BatchCreateEventsRequest,
+    ) -> Result<ApiResult, Error> {
+        let uri = format!("{}/v1/batch/events", String::as_str(&self.host.clone()));
+        let url = Url::parse(uri.as_str()).unwrap();
+        let res = self.authorized_post(url).json(request).send().await?;
+        let result = ApiResult {
+            status: res.status().as_u16(),
+            response: res,
+        };
+        Ok(result)
+    }
+
+    /// Attests many events with `POST /v1/batch/attestations`, the response holding the result of
+    /// each.
+    pub async fn post_attestations(
+        &self,
+        request: &BatchAttestationRequest,
+    ) -> Result<ApiResult, Error> {
+        let uri = format!(
+            "{}/v1/batch/attestations",
+            String::as_str(&self.host.clone())
+        );
+        let url = Url::parse(uri.as_str()).unwrap();
+        let res = self.authorized_post(url).json(request).send().await?;
+        let result = ApiResult {
+            status: res.status().as_u16(),
+            response: res,
+        };
+        Ok(result)
+    }
+
+    /// Creates an event with the deprecated `GET /v1/create_event/{uuid}`, see
+    /// [`OracleBackendClient::post_event`].
     pub async fn create_event(&self, uuid: String) -> Result<ApiResult, Error> {
         let uri = format!(
             "{}/v1/create_event/{}?maturation=2022-10-08T13:48:00Z",
@@ -165,7 +338,7 @@ impl OracleBackendClient {
             uuid.as_str()
         );
         let url = Url::parse(uri.as_str()).unwrap();
-        let res = self.client.get(url).send().await?;
+        let res = self.authorized_get(url).send().await?;
         let result = ApiResult {
             status: res.status().as_u16(),
             response: res,
@@ -173,6 +346,8 @@ impl OracleBackendClient {
         Ok(result)
     }
 
+    /// Attests an event with the deprecated `GET /v1/attest/{uuid}`, see
+    /// [`OracleBackendClient::post_attestation`].
     pub async fn get_attestation(&self, uuid: String, outcome: String) -> Result<ApiResult, Error> {
         let uri = format!(
             "{}/v1/attest/{}?outcome={}",
@@ -181,7 +356,7 @@ impl OracleBackendClient {
             outcome.as_str()
         );
         let url = Url::parse(uri.as_str()).unwrap();
-        let res = self.client.get(url).send().await?;
+        let res = self.authorized_get(url).send().await?;
         let result = ApiResult {
             status: res.status().as_u16(),
             response: res,
@@ -315,6 +490,89 @@ impl StorageApiClient {
         }
     }
 
+    /// Returns at most `limit` of the events whose event id starts with `prefix`, in order of
+    /// event id: the first ones after `cursor`, or the last ones before it if `reverse` is set.
+    pub async fn get_events_page(
+        &self,
+        prefix: &str,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<Event>, ApiError> {
+        let uri = format!("{}/events", String::as_str(&self.host.clone()));
+        let mut url = Url::parse(uri.as_str()).unwrap();
+        url.query_pairs_mut()
+            .append_pair("prefix", prefix)
+            .append_pair("limit", &limit.to_string())
+            .append_pair("reverse", &reverse.to_string());
+        if let Some(cursor) = cursor {
+            url.query_pairs_mut().append_pair("cursor", &cursor);
+        }
+        let res = match self.client.get(url).send().await {
+            Ok(result) => result,
+            Err(e) => {
+                return Err(ApiError {
+                    message: e.to_string(),
+                    status: 0,
+                })
+            }
+        };
+        let status = res.status();
+        if status.is_success() {
+            let status_clone = status.clone();
+            let events: Vec<Event> = res.json().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Ok(events)
+        } else {
+            let status_clone = status.clone();
+            let msg: String = res.text().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Err(ApiError {
+                message: msg,
+                status: status_clone.as_u16(),
+            })
+        }
+    }
+
+    /// Returns the number of events whose event id starts with `prefix`.
+    pub async fn count_events(&self, prefix: &str) -> Result<u64, ApiError> {
+        let uri = format!("{}/events/count", String::as_str(&self.host.clone()));
+        let mut url = Url::parse(uri.as_str()).unwrap();
+        url.query_pairs_mut().append_pair("prefix", prefix);
+        let res = match self.client.get(url).send().await {
+            Ok(result) => result,
+            Err(e) => {
+                return Err(ApiError {
+                    message: e.to_string(),
+                    status: 0,
+                })
+            }
+        };
+        let status = res.status();
+        if status.is_success() {
+            let status_clone = status.clone();
+            let count: u64 = res.json().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Ok(count)
+        } else {
+            let status_clone = status.clone();
+            let msg: String = res.text().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Err(ApiError {
+                message: msg,
+                status: status_clone.as_u16(),
+            })
+        }
+    }
+
     pub async fn get_event(&self, uuid: String) -> Result<Option<Event>, ApiError> {
         let uri = format!(
             "{}/events/{}",
@@ -510,6 +768,47 @@ impl StorageApiClient {
         }
     }
 
+    /// Creates the given events, or updates them if they exist, in a single transaction.
+    pub async fn upsert_events(&self, events: Vec<NewEvent>) -> Result<Vec<Event>, ApiError> {
+        let uri = format!("{}/events", String::as_str(&self.host.clone()));
+        let url = Url::parse(uri.as_str()).unwrap();
+        let res = match self
+            .client
+            .put(url)
+            .header("Content-Type", "application/json")
+            .json(&events)
+            .send()
+            .await
+        {
+            Ok(result) => result,
+            Err(e) => {
+                return Err(ApiError {
+                    message: e.to_string(),
+                    status: 0,
+                })
+            }
+        };
+        let status = res.status();
+        if status.is_success() {
+            let status_clone = status.clone();
+            let events: Vec<Event> = res.json().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Ok(events)
+        } else {
+            let status_clone = status.clone();
+            let msg: String = res.text().await.map_err(|e| ApiError {
+                message: e.to_string(),
+                status: status_clone.as_u16(),
+            })?;
+            Err(ApiError {
+                message: msg,
+                status: status_clone.as_u16(),
+            })
+        }
+    }
+
     pub async fn update_event(&self, uuid: String, event: UpdateEvent) -> Result<(), ApiError> {
         let uri = format!(
             "{}/events/{}",
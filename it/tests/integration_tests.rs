@@ -24,7 +24,10 @@ fn create_wallet_client(world: &mut DlcLinkWorld, address: String) {
 
 #[given(expr = "an oracle backend client with address {word}")]
 fn create_oracle_client(world: &mut DlcLinkWorld, address: String) {
-    world.oracle_client = OracleBackendClient::new(address);
+    world.oracle_client = match std::env::var("ORACLE_API_KEY") {
+        Ok(api_key) => OracleBackendClient::new(address).with_api_key(api_key),
+        Err(_) => OracleBackendClient::new(address),
+    };
 }
 
 #[when(expr = "accept message: {word} as '{word}'")]
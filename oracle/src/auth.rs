@@ -0,0 +1,177 @@
+use crate::oracle::{
+    secret_key::{use_vault, vault_key_path},
+    vault::get_api_keys,
+};
+use displaydoc::Display;
+use log::{info, warn};
+use secp256k1_zkp::hashes::{sha256, Hash};
+use serde::{Deserialize, Serialize};
+use std::{collections::HashSet, env, fs, path::PathBuf};
+use vaultrs::error::ClientError;
+
+const DEFAULT_API_KEY_FILE: &str = "config/api_keys.json";
+
+/// What an API key allows its holder to do.
+#[derive(Clone, Copy, Debug, Deserialize, Display, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Scope {
+    /// create events
+    CreateEvent,
+    /// attest events
+    Attest,
+}
+
+/// An API key as configured in `api_keys.json`. Keys are configured by their SHA-256 hash, so
+/// that the configuration does not hold them.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ApiKeyConfig {
+    /// identity of the holder of the key, logged with each call they make
+    pub id: String,
+    /// hex-encoded SHA-256 hash of the key
+    pub key_sha256: String,
+    pub scopes: Vec<Scope>,
+}
+
+/// API keys allowed to call the routes which create or attest events.
+#[derive(Clone)]
+pub struct ApiKeys {
+    keys: Vec<(String, [u8; 32], Vec<Scope>)>,
+    // set to let anyone call every route, for local development only
+    disabled: bool,
+}
+
+impl ApiKeys {
+    /// Validates `keys`, which must have distinct ids and hashes.
+    pub fn new(keys: Vec<ApiKeyConfig>) -> Result<Self, String> {
+        let mut ids = HashSet::new();
+        let mut parsed: Vec<(String, [u8; 32], Vec<Scope>)> = Vec::with_capacity(keys.len());
+        for key in keys {
+            if !ids.insert(key.id.clone()) {
+                return Err(format!("API key {} is configured more than once", key.id));
+            }
+            let hash = <[u8; 32] as hex::FromHex>::from_hex(&key.key_sha256)
+                .map_err(|e| format!("invalid key_sha256 of API key {}: {}", key.id, e))?;
+            if parsed.iter().any(|(_, other, _)| *other == hash) {
+                return Err(format!("API key {} reuses the key of another", key.id));
+            }
+            parsed.push((key.id, hash, key.scopes));
+        }
+        Ok(ApiKeys {
+            keys: parsed,
+            disabled: false,
+        })
+    }
+
+    /// Returns API keys letting anyone call every route.
+    pub fn disabled() -> Self {
+        ApiKeys {
+            keys: vec![],
+            disabled: true,
+        }
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the id and scopes of the API key `key`, if configured.
+    pub fn authenticate(&self, key: &str) -> Option<(&str, &[Scope])> {
+        let hash = sha256::Hash::hash(key.as_bytes()).into_inner();
+        self.keys
+            .iter()
+            .find(|(_, key_hash, _)| constant_time_eq(key_hash, &hash))
+            .map(|(id, _, scopes)| (id.as_str(), scopes.as_slice()))
+    }
+}
+
+fn constant_time_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    a.iter().zip(b).fold(0, |acc, (a, b)| acc | (a ^ b)) == 0
+}
+
+/// Loads the API keys from `api_key_file` if given, else from Vault if `VAULT_ENABLED` is set,
+/// else from `config/api_keys.json`. Without any configured key, events can neither be created
+/// nor attested through the API, unless `ADMIN_AUTH_DISABLED` is set.
+pub async fn load_api_keys(api_key_file: Option<PathBuf>) -> anyhow::Result<ApiKeys> {
+    let auth_disabled: bool = env::var("ADMIN_AUTH_DISABLED")
+        .unwrap_or("false".to_string())
+        .parse()?;
+    if auth_disabled {
+        warn!("ADMIN_AUTH_DISABLED is set, anyone can create and attest events");
+        return Ok(ApiKeys::disabled());
+    }
+
+    let keys: Vec<ApiKeyConfig> = match api_key_file {
+        None if use_vault() => {
+            let path = format!("{}/api_keys", vault_key_path());
+            info!("reading API keys from vault at {}", path);
+            match get_api_keys(&path, "secret").await {
+                Ok(secret) => secret.keys,
+                Err(ClientError::APIError { code: 404, .. }) => vec![],
+                Err(err) => return Err(err.into()),
+            }
+        }
+        api_key_file => {
+            let path = api_key_file.unwrap_or_else(|| PathBuf::from(DEFAULT_API_KEY_FILE));
+            if path.exists() {
+                info!("reading API keys from {}", path.to_string_lossy());
+                serde_json::from_str(&fs::read_to_string(path)?)?
+            } else {
+                vec![]
+            }
+        }
+    };
+    let api_keys = ApiKeys::new(keys).map_err(anyhow::Error::msg)?;
+    if api_keys.is_empty() {
+        warn!("no API keys configured, events can neither be created nor attested through the API");
+    }
+    Ok(api_keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn api_keys() -> ApiKeys {
+        ApiKeys::new(vec![ApiKeyConfig {
+            id: "backend".to_string(),
+            // SHA-256 of "secret"
+            key_sha256: "2bb80d537b1da3e38bd30361aa855686bde0eacd7162fef6a25fe97bf527a25b"
+                .to_string(),
+            scopes: vec![Scope::CreateEvent],
+        }])
+        .unwrap()
+    }
+
+    #[test]
+    fn authenticates_configured_keys() {
+        let api_keys = api_keys();
+        assert_eq!(
+            Some(("backend", &[Scope::CreateEvent][..])),
+            api_keys.authenticate("secret")
+        );
+        assert_eq!(None, api_keys.authenticate("other"));
+    }
+
+    #[test]
+    fn rejects_duplicate_keys() {
+        let key = ApiKeyConfig {
+            id: "backend".to_string(),
+            key_sha256: "2bb80d537b1da3e38bd30361aa855686bde0eacd7162fef6a25fe97bf527a25b"
+                .to_string(),
+            scopes: vec![],
+        };
+        assert!(ApiKeys::new(vec![key.clone(), key.clone()]).is_err());
+        assert!(ApiKeys::new(vec![
+            key.clone(),
+            ApiKeyConfig {
+                id: "other".to_string(),
+                ..key
+            }
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,459 @@
+use clap::Subcommand;
+use dlc_clients::{AttestationOutcome, AttestationRequest, OracleBackendClient};
+use secp256k1_zkp::{All, Secp256k1};
+use sibyls::{
+    oracle::{
+        postgres_database_url, read_export,
+        secret_key::{generate_keyring, read_keyring},
+        signer::{LocalSigner, SignerPolicy, SignerServer},
+        sled_path as handler_sled_path, storage_api_enabled,
+        storage_api_endpoint as handler_storage_api_endpoint, write_export, EventHandler, Oracle,
+        StoredEvent,
+    },
+    AssetPair, OracleConfig,
+};
+use std::{
+    collections::HashMap,
+    env,
+    fs::{File, Permissions},
+    io::{self, BufReader, BufWriter},
+    os::unix::fs::PermissionsExt,
+    path::PathBuf,
+};
+use time::format_description::well_known::Rfc3339;
+
+use crate::{attest_event, get_oracle, parse_asset_pair, parse_database_entry};
+
+/// Operator commands, run against the key store and the event database without starting the
+/// server.
+#[derive(Subcommand)]
+pub enum Command {
+    /// Generates the oracle key; fails if the oracle already has one, see rotate-key
+    Keygen,
+
+    /// Prints the public key of the active oracle key
+    ShowPubkey {
+        /// Also print the retired keys, with the time they were retired
+        #[clap(long)]
+        all: bool,
+    },
+
+    /// Retires the active oracle key and generates a new one, which announces events from the next
+    /// start of the oracle on; retired keys keep attesting to the events they announced
+    RotateKey,
+
+    /// Runs the signer process, which holds the oracle keys and signs for oracles started with
+    /// --signer-socket, refusing premature attestations, nonce reuse and conflicting announcements
+    Signer {
+        /// Path of the unix socket to listen on, which only the user running the signer may use
+        #[clap(long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath, default_value = "sibyls-signer.sock")]
+        socket: PathBuf,
+
+        /// Path of the database recording the signed announcements and outcomes
+        #[clap(long, parse(from_os_str), value_name = "DIR", value_hint = clap::ValueHint::DirPath, default_value = "signer_db")]
+        db_path: PathBuf,
+
+        /// Sign attestations before the attestation grace period when the oracle gives a reason
+        #[clap(long)]
+        allow_early_attestation: bool,
+
+        /// Record the events of the event database again, as on the first start of the signer,
+        /// e.g. once events are imported
+        #[clap(long)]
+        seed: bool,
+    },
+
+    /// Writes an export of the stored events to FILE, or to stdout if not provided
+    ExportEvents {
+        /// Only export the events of this asset pair
+        #[clap(long)]
+        asset_pair: Option<String>,
+
+        /// Also export the secret nonces which unattested events announced by older versions of
+        /// the oracle are stored with; the export must then be protected like the oracle key
+        #[clap(long)]
+        include_secret_nonces: bool,
+
+        #[clap(parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        file: Option<PathBuf>,
+    },
+
+    /// Stores the events exported to FILE, or read from stdin if not provided
+    ImportEvents {
+        #[clap(parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+        file: Option<PathBuf>,
+
+        /// Replace the events which are already stored, which are kept otherwise; an event is only
+        /// replaced by the same announcement, attested the same way if it is attested
+        #[clap(long)]
+        overwrite: bool,
+
+        /// Import events whose announcement or attestation does not verify
+        #[clap(long)]
+        no_verify: bool,
+    },
+
+    /// Copies the stored events to another event database, checking every event is copied exactly
+    MigrateEvents {
+        /// Event database to copy the events to, from the one the oracle is configured with
+        #[clap(long, arg_enum)]
+        to: Backend,
+
+        /// Path of the destination sled database; defaults to ORACLE_EVENTS_DB_PATH, else
+        /// "events_db"
+        #[clap(long, parse(from_os_str), value_name = "DIR", value_hint = clap::ValueHint::DirPath)]
+        sled_path: Option<PathBuf>,
+
+        /// Endpoint of the destination storage api; defaults to STORAGE_API_ENDPOINT, else
+        /// "http://localhost:8100"
+        #[clap(long)]
+        storage_api_endpoint: Option<String>,
+
+        /// Url of the destination postgres database, whose migrations are applied first
+        #[clap(long)]
+        database_url: Option<String>,
+
+        /// Replace the events which the destination stores with another record, instead of failing
+        #[clap(long)]
+        overwrite: bool,
+    },
+
+    /// Verifies every stored announcement and attestation; fails if any is invalid
+    VerifyDb,
+
+    /// Attests an event through the running oracle, or directly in the event database with
+    /// --offline
+    Attest {
+        /// Id of the event to attest
+        uuid: String,
+
+        /// Asset pair of the event; required when several are configured
+        #[clap(long)]
+        asset_pair: Option<String>,
+
+        /// Outcome to attest to; if not provided, the price reported by the pricefeeds
+        #[clap(long)]
+        outcome: Option<String>,
+
+        /// Reason to attest before maturation, which is stored with the attestation
+        #[clap(long)]
+        early_attestation_reason: Option<String>,
+
+        /// Attest in the event database instead of through the running oracle, which must be
+        /// stopped
+        #[clap(long)]
+        offline: bool,
+
+        /// URL of the running oracle, which is authenticated with the API key in ORACLE_API_KEY
+        #[clap(long, default_value = "http://localhost:8080")]
+        url: String,
+    },
+}
+
+impl Command {
+    /// Returns whether the command only reads the event database, which is then not migrated.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Command::ExportEvents { .. } | Command::VerifyDb)
+    }
+}
+
+/// A kind of event database.
+#[derive(Clone, Copy, PartialEq, Eq, clap::ArgEnum)]
+pub enum Backend {
+    Sled,
+    StorageApi,
+    /// requires sibyls to be built with the postgres feature
+    Postgres,
+}
+
+// returns the kind of event database the oracle is configured with, see `EventHandler::new`
+fn configured_backend() -> Backend {
+    if postgres_database_url().is_some() {
+        Backend::Postgres
+    } else if storage_api_enabled() {
+        Backend::StorageApi
+    } else {
+        Backend::Sled
+    }
+}
+
+pub async fn keygen(secp: &Secp256k1<All>, secret_key_file: Option<PathBuf>) -> anyhow::Result<()> {
+    let keyring = generate_keyring(secp, secret_key_file).await?;
+    println!("{}", keyring.active().public_key());
+    Ok(())
+}
+
+pub async fn show_pubkey(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+    all: bool,
+) -> anyhow::Result<()> {
+    let keyring = read_keyring(secp, secret_key_file).await?;
+    if !all {
+        println!("{}", keyring.active().public_key());
+        return Ok(());
+    }
+    for key in keyring.keys() {
+        match key.retired_at {
+            Some(retired_at) => println!(
+                "{} retired {}",
+                key.keypair.public_key(),
+                retired_at.format(&Rfc3339)?
+            ),
+            None => println!("{} active", key.keypair.public_key()),
+        }
+    }
+    Ok(())
+}
+
+pub async fn signer(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+    oracle_config: &OracleConfig,
+    socket: PathBuf,
+    db_path: PathBuf,
+    allow_early_attestation: bool,
+    seed: bool,
+) -> anyhow::Result<()> {
+    let keyring = read_keyring(secp, secret_key_file).await?;
+    info!(
+        "opening signer database at {}",
+        db_path.as_os_str().to_string_lossy()
+    );
+    let db = sled::open(db_path)?;
+    let server = SignerServer::new(
+        LocalSigner::new(keyring, secp.clone()),
+        SignerPolicy {
+            attestation_grace_period: oracle_config.attestation_grace_period,
+            allow_early_attestation,
+        },
+        &db,
+    )?;
+    // the signer only attests the events it announced, or recorded from the event database
+    if seed || !server.is_seeded()? {
+        let seeded = server.seed(&stored_events().await?)?;
+        info!("recorded {} events of the event database", seeded);
+    }
+    server.serve(&socket).await?;
+    Ok(())
+}
+
+// returns the events of every asset pair stored in the event database the oracle is configured
+// with, including those stored before asset pairs had their own namespace
+async fn stored_events() -> anyhow::Result<Vec<StoredEvent>> {
+    let event_storage = EventHandler::new()?;
+    let legacy_asset_pair: AssetPair = crate::LEGACY_ASSET_PAIR
+        .parse()
+        .map_err(anyhow::Error::msg)?;
+    let mut events = vec![];
+    for (_, record) in event_storage.get_all().await? {
+        events.push(StoredEvent::from_record(&record, &legacy_asset_pair)?.0);
+    }
+    for asset_pair in event_storage.asset_pairs().await? {
+        for (_, record) in event_storage.for_asset_pair(&asset_pair)?.get_all().await? {
+            events.push(StoredEvent::from_record(&record, &asset_pair)?.0);
+        }
+    }
+    Ok(events)
+}
+
+pub async fn export_events(
+    oracles: &HashMap<AssetPair, Oracle>,
+    asset_pair: Option<String>,
+    include_secret_nonces: bool,
+    file: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    let oracles = match parse_asset_pair(&asset_pair)? {
+        Some(asset_pair) => vec![get_oracle(oracles, Some(&asset_pair))?],
+        None => oracles.values().collect(),
+    };
+    let mut events = vec![];
+    for oracle in oracles {
+        events.extend(oracle.get_events().await?);
+    }
+    let with_secret_nonces = events
+        .iter()
+        .filter(|event| event.attestation.is_none() && event.outstanding_sk_nonces.is_some())
+        .count();
+    if with_secret_nonces > 0 && !include_secret_nonces {
+        warn!(
+            "{} unattested events are stored with secret nonces, which are left out of the export: \
+             they cannot be attested once imported unless exported with --include-secret-nonces",
+            with_secret_nonces
+        );
+    }
+    match file {
+        Some(path) => {
+            let file = File::create(path)?;
+            if include_secret_nonces {
+                // exports holding secret nonces are only readable by their owner, like the key
+                file.set_permissions(Permissions::from_mode(0o600))?;
+            }
+            write_export(&mut BufWriter::new(file), &events, include_secret_nonces)?
+        }
+        None => write_export(
+            &mut BufWriter::new(io::stdout()),
+            &events,
+            include_secret_nonces,
+        )?,
+    }
+    info!("exported {} events", events.len());
+    Ok(())
+}
+
+pub async fn import_events(
+    oracles: &HashMap<AssetPair, Oracle>,
+    secp: &Secp256k1<All>,
+    file: Option<PathBuf>,
+    overwrite: bool,
+    no_verify: bool,
+) -> anyhow::Result<()> {
+    let exported = match file {
+        Some(path) => read_export(BufReader::new(File::open(path)?))?,
+        None => read_export(io::stdin().lock())?,
+    };
+    let mut events: HashMap<AssetPair, Vec<StoredEvent>> = HashMap::new();
+    for event in exported {
+        if !no_verify {
+            event.verify(secp)?;
+        }
+        events
+            .entry(event.asset_pair.clone())
+            .or_default()
+            .push(event);
+    }
+    // events are only imported once all are known to be of a configured asset pair
+    for asset_pair in events.keys() {
+        get_oracle(oracles, Some(asset_pair))?;
+    }
+    for (asset_pair, events) in events {
+        let imported = oracles[&asset_pair]
+            .import_events(events, overwrite)
+            .await?;
+        info!("imported {} events of {}", imported, asset_pair);
+    }
+    Ok(())
+}
+
+pub async fn migrate_events(
+    source: &EventHandler,
+    to: Backend,
+    sled_path: Option<PathBuf>,
+    storage_api_endpoint: Option<String>,
+    database_url: Option<String>,
+    overwrite: bool,
+) -> anyhow::Result<()> {
+    let configured = configured_backend();
+    let destination = match to {
+        Backend::Sled if configured == Backend::Sled => {
+            anyhow::bail!("the oracle already stores its events in sled")
+        }
+        Backend::StorageApi if configured == Backend::StorageApi => {
+            anyhow::bail!("the oracle already stores its events through the storage api")
+        }
+        Backend::Postgres if configured == Backend::Postgres => {
+            anyhow::bail!("the oracle already stores its events in postgres")
+        }
+        Backend::Sled => EventHandler::sled(
+            &sled_path.map_or_else(handler_sled_path, |path| path.to_string_lossy().to_string()),
+        )?,
+        Backend::StorageApi => EventHandler::storage_api(
+            storage_api_endpoint.unwrap_or_else(handler_storage_api_endpoint),
+        ),
+        Backend::Postgres => {
+            let database_url = database_url.ok_or_else(|| {
+                anyhow::anyhow!("--database-url is required to migrate to postgres")
+            })?;
+            postgres_destination(&database_url)?
+        }
+    };
+    let copied = source.copy_events(&destination, overwrite).await?;
+    println!("copied {} events", copied);
+    Ok(())
+}
+
+#[cfg(feature = "postgres")]
+fn postgres_destination(database_url: &str) -> anyhow::Result<EventHandler> {
+    Ok(EventHandler::postgres(
+        database_url,
+        sibyls::oracle::postgres_pool_size()?,
+        true,
+    )?)
+}
+
+#[cfg(not(feature = "postgres"))]
+fn postgres_destination(_database_url: &str) -> anyhow::Result<EventHandler> {
+    anyhow::bail!("sibyls was built without the postgres feature")
+}
+
+pub async fn verify_db(
+    oracles: &HashMap<AssetPair, Oracle>,
+    secp: &Secp256k1<All>,
+) -> anyhow::Result<()> {
+    let mut verified = 0;
+    let mut invalid = 0;
+    for (asset_pair, oracle) in oracles {
+        for (uuid, record) in oracle.event_handler.get_all().await? {
+            let result = StoredEvent::from_record(&record, asset_pair)
+                .and_then(|(event, _)| event.verify(secp));
+            match result {
+                Ok(()) => verified += 1,
+                Err(err) => {
+                    println!("{} {}: {}", asset_pair, uuid, err);
+                    invalid += 1;
+                }
+            }
+        }
+    }
+    info!("verified {} events", verified);
+    if invalid > 0 {
+        anyhow::bail!("{} invalid events", invalid);
+    }
+    Ok(())
+}
+
+pub async fn attest(
+    oracles: &HashMap<AssetPair, Oracle>,
+    uuid: String,
+    asset_pair: Option<String>,
+    outcome: Option<String>,
+    early_attestation_reason: Option<String>,
+) -> anyhow::Result<()> {
+    let asset_pair = parse_asset_pair(&asset_pair)?;
+    let oracle = get_oracle(oracles, asset_pair.as_ref())?;
+    let event = attest_event(oracle, uuid, outcome, early_attestation_reason, None).await?;
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&parse_database_entry(event))?
+    );
+    Ok(())
+}
+
+pub async fn attest_online(
+    url: String,
+    uuid: String,
+    asset_pair: Option<String>,
+    outcome: Option<String>,
+    early_attestation_reason: Option<String>,
+) -> anyhow::Result<()> {
+    let mut client = OracleBackendClient::new(url);
+    if let Ok(api_key) = env::var("ORACLE_API_KEY") {
+        client = client.with_api_key(api_key);
+    }
+    let request = AttestationRequest {
+        asset_pair,
+        outcome: outcome.map(|outcome| match outcome.parse() {
+            Ok(outcome) => AttestationOutcome::Numeric(outcome),
+            Err(_) => AttestationOutcome::Enum(outcome),
+        }),
+        early_attestation_reason,
+        idempotency_key: None,
+    };
+    let result = client.post_attestation(uuid, &request).await?;
+    let body = result.response.text().await?;
+    if !(200..300).contains(&result.status) {
+        anyhow::bail!("oracle responded with status {}: {}", result.status, body);
+    }
+    println!("{}", body);
+    Ok(())
+}
@@ -1,23 +1,112 @@
-use crate::oracle::EventDescriptor;
+use crate::oracle::{
+    pricefeeds::{PriceAggregationConfig, PriceFeedConfig},
+    EventDescriptor,
+};
 use serde::{Deserialize, Serialize};
-use std::fmt::{self, Debug, Display, Formatter};
+use std::{
+    collections::HashSet,
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
 use time::{serde::format_description, Duration, Time};
 
-#[derive(Copy, Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
-pub enum AssetPair {
-    BTCUSD,
+/// Identifier of an asset pair as configured in `asset_pair.json`, e.g. `BTCUSD`.
+///
+/// Ids are made of ASCII alphanumeric characters, `_` and `-`, as they name the event namespace of
+/// the asset pair in storage.
+#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct AssetPair(String);
+
+impl AssetPair {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl TryFrom<String> for AssetPair {
+    type Error = String;
+
+    fn try_from(id: String) -> Result<Self, Self::Error> {
+        if id.is_empty() {
+            return Err("asset pair id must not be empty".to_string());
+        }
+        if !id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+        {
+            return Err(format!(
+                "invalid asset pair id {:?}, only ASCII alphanumeric characters, '_' and '-' are allowed",
+                id
+            ));
+        }
+        Ok(Self(id))
+    }
+}
+
+impl FromStr for AssetPair {
+    type Err = String;
+
+    fn from_str(id: &str) -> Result<Self, Self::Err> {
+        id.to_string().try_into()
+    }
+}
+
+impl From<AssetPair> for String {
+    fn from(asset_pair: AssetPair) -> Self {
+        asset_pair.0
+    }
 }
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct AssetPairInfo {
     pub asset_pair: AssetPair,
     pub event_descriptor: EventDescriptor,
+    #[serde(default)]
+    pub pricefeeds: Vec<PriceFeedConfig>,
+    #[serde(default)]
+    pub price_aggregation: PriceAggregationConfig,
+    #[serde(default)]
+    pub out_of_range_policy: OutOfRangePolicy,
+}
+
+/// What to do with outcomes of digit decomposition events which do not fit in the announced
+/// digits.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutOfRangePolicy {
+    /// refuse to attest
+    Reject,
+    /// attest to the largest outcome that fits instead, as expected by payout curves ending at
+    /// the maximum outcome
+    ClampToMax,
+}
+
+impl Default for OutOfRangePolicy {
+    fn default() -> Self {
+        OutOfRangePolicy::Reject
+    }
 }
 
 impl Display for AssetPair {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        Debug::fmt(self, f)
+        f.write_str(&self.0)
+    }
+}
+
+/// Checks that the asset pairs of `asset_pair_infos` are distinct, so that each has its own oracle
+/// and event namespace.
+pub fn validate_asset_pair_infos(asset_pair_infos: &[AssetPairInfo]) -> Result<(), String> {
+    let mut asset_pairs = HashSet::new();
+    for asset_pair_info in asset_pair_infos {
+        if !asset_pairs.insert(&asset_pair_info.asset_pair) {
+            return Err(format!(
+                "asset pair {} is configured more than once",
+                asset_pair_info.asset_pair
+            ));
+        }
     }
+    Ok(())
 }
 
 format_description!(standard_time, Time, "[hour]:[minute]");
@@ -81,4 +170,7 @@ pub struct OracleConfig {
     pub frequency: Duration,
     #[serde(with = "standard_duration")]
     pub announcement_offset: Duration,
+    /// how long before maturation events may already be attested, e.g. to absorb clock skew
+    #[serde(default, with = "standard_duration")]
+    pub attestation_grace_period: Duration,
 }
@@ -1,5 +1,5 @@
 use displaydoc::Display;
-use sibyls::oracle::OracleError;
+use sibyls::oracle::{signer::SignerError, OracleError};
 use thiserror::Error;
 
 #[allow(clippy::enum_variant_names)]
@@ -8,12 +8,45 @@ pub enum SibylsError {
     /// asset pair {0} not recorded
     UnrecordedAssetPairError(sibyls::AssetPair),
 
+    /// asset pair must be given when several are configured
+    MissingAssetPairError,
+
+    /// invalid asset pair: {0}
+    InvalidAssetPairError(String),
+
+    /// invalid request: {0}
+    InvalidRequestError(String),
+
+    /// page size must be between 1 and 1000, got {0}
+    InvalidPageSizeError(u32),
+
+    /// batch size must be between 1 and 1000, got {0}
+    InvalidBatchSizeError(usize),
+
+    /// invalid cursor {0}
+    InvalidCursorError(String),
+
+    /// pages are not numbered, pass the X-Next-Cursor of the previous page as cursor
+    PageNumberError,
+
+    /// invalid oracle message: {0}
+    InvalidMessageError(String),
+
+    /// missing or unknown API key
+    UnauthorizedError,
+
+    /// API key {0} is not allowed to {1}
+    ForbiddenError(String, sibyls::auth::Scope),
+
     /// datetime RFC3339 parsing error: {0}
     DatetimeParseError(#[from] time::error::Parse),
 
     /// oracle event with uuid {0} not found
     OracleEventNotFoundError(String),
 
+    /// no oracle key {0}
+    UnknownOracleError(String),
+
     /// database error: {0}
     DatabaseError(#[from] sled::Error),
 
@@ -26,9 +59,38 @@ pub enum SibylsError {
 
 impl actix_web::error::ResponseError for SibylsError {
     fn status_code(&self) -> actix_web::http::StatusCode {
-        if let SibylsError::DatetimeParseError(_) = self {
-            return actix_web::http::StatusCode::BAD_REQUEST;
+        match self {
+            SibylsError::DatetimeParseError(_)
+            | SibylsError::MissingAssetPairError
+            | SibylsError::InvalidAssetPairError(_)
+            | SibylsError::InvalidRequestError(_)
+            | SibylsError::InvalidPageSizeError(_)
+            | SibylsError::InvalidBatchSizeError(_)
+            | SibylsError::InvalidCursorError(_)
+            | SibylsError::PageNumberError
+            | SibylsError::InvalidMessageError(_)
+            | SibylsError::OracleDatabaseError(OracleError::InvalidEventDescriptorError(_))
+            | SibylsError::OracleDatabaseError(OracleError::InvalidOutcomeError(_))
+            | SibylsError::OracleDatabaseError(OracleError::PrematureAttestationError(..))
+            | SibylsError::OracleDatabaseError(OracleError::SignerError(
+                SignerError::PrematureAttestationError(..),
+            ))
+            | SibylsError::OracleDatabaseError(OracleError::OutcomeOutOfRangeError(_)) => {
+                actix_web::http::StatusCode::BAD_REQUEST
+            }
+            SibylsError::UnknownOracleError(_)
+            | SibylsError::OracleEventNotFoundError(_)
+            | SibylsError::UnrecordedAssetPairError(_) => actix_web::http::StatusCode::NOT_FOUND,
+            SibylsError::UnauthorizedError => actix_web::http::StatusCode::UNAUTHORIZED,
+            SibylsError::ForbiddenError(..) => actix_web::http::StatusCode::FORBIDDEN,
+            SibylsError::OracleDatabaseError(OracleError::AlreadyAttestedError(_))
+            | SibylsError::OracleDatabaseError(OracleError::IdempotencyKeyConflictError(_))
+            | SibylsError::OracleDatabaseError(OracleError::SignerError(
+                SignerError::NonceReuseError(_)
+                | SignerError::AnnouncementConflictError(_)
+                | SignerError::UnknownEventError(_),
+            )) => actix_web::http::StatusCode::CONFLICT,
+            _ => actix_web::http::StatusCode::INTERNAL_SERVER_ERROR,
         }
-        actix_web::http::StatusCode::INTERNAL_SERVER_ERROR
     }
 }
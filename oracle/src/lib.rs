@@ -1,3 +1,4 @@
+pub mod auth;
 pub mod common;
 
 pub use common::*;
@@ -5,5 +6,7 @@ pub use common::*;
 pub mod oracle;
 
 pub use oracle::oracle_queryable::messaging::{
-    Announcement, Attestation, EventDescriptor, OracleEvent,
+    suredbits_encode_announcement, suredbits_encode_attestation, Announcement, Attestation,
+    EnumEventDescriptor, EventDescriptor, OracleEvent, OracleEventDescriptor,
 };
+pub use oracle::verification::{verify_announcement, verify_attestation, VerificationError};
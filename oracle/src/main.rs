@@ -1,62 +1,65 @@
 #[macro_use]
 extern crate log;
 extern crate core;
-use ::hex::ToHex;
-use actix_web::{get, web, App, HttpResponse, HttpServer};
+use ::hex::{FromHex, ToHex};
+use actix_web::{get, post, web, App, HttpRequest, HttpResponse, HttpServer};
 use clap::Parser;
 
-use core::ptr;
-use secp256k1_sys::{
-    types::{c_int, c_uchar, c_void, size_t},
-    CPtr, SchnorrSigExtraParams,
-};
 use secp256k1_zkp::{
-    constants::SCHNORR_SIGNATURE_SIZE, hashes::*, rand, schnorr::Signature as SchnorrSignature,
-    All, KeyPair, Message, Secp256k1, Signing, XOnlyPublicKey as SchnorrPublicKey,
+    hashes::{sha256, Hash},
+    All, Secp256k1, XOnlyPublicKey as SchnorrPublicKey,
 };
-use secp256k1_zkp_5::rand::RngCore;
 use std::{env, io::Cursor};
 
 use serde::{Deserialize, Serialize};
 
-use sled::IVec;
-use std::path::PathBuf;
 use std::{
+    cmp::Ordering,
     collections::HashMap,
     fs::{self, File},
     io::Read,
+    path::PathBuf,
+    sync::Arc,
 };
 use time::{format_description::well_known::Rfc3339, OffsetDateTime};
 
+use dlc_clients::{
+    AttestationRequest, BatchAttestationRequest, BatchCreateEventsRequest, CreateEventRequest,
+    EventDescriptorOverrides,
+};
 use sibyls::{
-    oracle::{oracle_queryable::messaging::OracleAnnouncementHash, DbValue, Oracle},
-    Announcement, AssetPair, AssetPairInfo, Attestation, EventDescriptor, OracleConfig,
-    OracleEvent,
+    auth::{load_api_keys, ApiKeys, Scope},
+    oracle::{
+        decode_announcement, decode_attestation, oracle_scheduler,
+        signer::{LocalSigner, RemoteSigner, Signer, SignerKey},
+        EventHandler, EventToAttest, EventToCreate, Oracle, OracleError, StoredEvent,
+    },
+    validate_asset_pair_infos, verify_announcement, verify_attestation, AssetPair, AssetPairInfo,
+    EnumEventDescriptor, EventDescriptor, OracleConfig, OracleEventDescriptor, OutOfRangePolicy,
 };
 
+mod cli;
 mod error;
+use cli::Command;
 use error::SibylsError;
-use sibyls::oracle::secret_key::get_or_generate_keypair;
 
-extern "C" fn constant_nonce_fn(
-    nonce32: *mut c_uchar,
-    _: *const c_uchar,
-    _: size_t,
-    _: *const c_uchar,
-    _: *const c_uchar,
-    _: *const c_uchar,
-    _: size_t,
-    data: *mut c_void,
-) -> c_int {
-    unsafe {
-        ptr::copy_nonoverlapping(data as *const c_uchar, nonce32, 32);
-    }
-    1
-}
+// the only asset pair before asset pairs were configurable
+const LEGACY_ASSET_PAIR: &str = "BTCUSD";
+const DEFAULT_PAGE_SIZE: u32 = 100;
+const MAX_PAGE_SIZE: u32 = 1000;
+const MAX_BATCH_SIZE: usize = 1000;
+// events read at most to list a page of announcements by event id, so that selective filters
+// bound the work of a request too
+const MAX_SCANNED_EVENTS: usize = 10000;
+// separates the asset pair from the uuid of the last listed event in announcement cursors
+const CURSOR_SEPARATOR: char = ':';
+use sibyls::oracle::secret_key::{get_or_generate_keyring, read_keyring, rotate_keyring};
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 enum SortOrder {
+    EventId,
+    ReverseEventId,
     Insertion,
     ReverseInsertion,
 }
@@ -65,20 +68,45 @@ enum SortOrder {
 #[serde(default, rename_all = "camelCase")]
 struct Filters {
     sort_by: SortOrder,
-    page: u32,
-    asset_pair: AssetPair,
+    cursor: Option<String>,
+    // pages used to be numbered, which is rejected rather than ignored
+    page: Option<u32>,
+    page_size: u32,
+    asset_pair: Option<AssetPair>,
     maturation: String,
-    outcome: Option<u64>,
+    maturation_after: Option<String>,
+    maturation_before: Option<String>,
+    attested: Option<bool>,
+    outcome: Option<String>,
+    outcomes: Option<String>,
+    early_attestation_reason: Option<String>,
+    base: Option<u16>,
+    is_signed: Option<bool>,
+    unit: Option<String>,
+    precision: Option<i32>,
+    num_digits: Option<u16>,
 }
 
 impl Default for Filters {
     fn default() -> Self {
         Filters {
-            sort_by: SortOrder::ReverseInsertion,
-            page: 0,
-            asset_pair: AssetPair::BTCUSD,
+            sort_by: SortOrder::EventId,
+            cursor: None,
+            page: None,
+            page_size: DEFAULT_PAGE_SIZE,
+            asset_pair: None,
             maturation: "".to_string(),
+            maturation_after: None,
+            maturation_before: None,
+            attested: None,
             outcome: None,
+            outcomes: None,
+            early_attestation_reason: None,
+            base: None,
+            is_signed: None,
+            unit: None,
+            precision: None,
+            num_digits: None,
         }
     }
 }
@@ -94,13 +122,159 @@ struct ApiOracleEvent {
     rust_attestation_json: Option<String>,
     rust_attestation: Option<String>,
     maturation: String,
-    outcome: Option<u64>,
+    outcome: Option<i64>,
+    unclamped_outcome: Option<i64>,
+    metadata: Option<serde_json::Map<String, serde_json::Value>>,
+}
+
+/// A page of the events listed by `GET /v1/announcements`.
+struct EventPage {
+    events: Vec<StoredEvent>,
+    /// cursor of the next page, unless all events were listed
+    next_cursor: Option<String>,
+}
+
+/// Filters of the events listed by `GET /v1/announcements`, besides their asset pair.
+struct EventFilters {
+    maturation_after: Option<OffsetDateTime>,
+    maturation_before: Option<OffsetDateTime>,
+    attested: Option<bool>,
+}
+
+impl EventFilters {
+    fn parse(filters: &Filters) -> Result<Self, SibylsError> {
+        let parse_maturation = |maturation: &Option<String>| {
+            maturation
+                .as_deref()
+                .map(|maturation| OffsetDateTime::parse(maturation, &Rfc3339))
+                .transpose()
+        };
+        Ok(EventFilters {
+            maturation_after: parse_maturation(&filters.maturation_after)?,
+            maturation_before: parse_maturation(&filters.maturation_before)?,
+            attested: filters.attested,
+        })
+    }
+
+    fn matches(&self, event: &StoredEvent) -> bool {
+        self.maturation_after
+            .map_or(true, |after| event.maturation >= after)
+            && self
+                .maturation_before
+                .map_or(true, |before| event.maturation < before)
+            && self
+                .attested
+                .map_or(true, |attested| event.attestation.is_some() == attested)
+    }
+}
+
+/// Result of an item of a batch request, with the status code of the equivalent single request.
+#[derive(Serialize)]
+struct ApiBatchResult {
+    uuid: String,
+    status: u16,
+    event: Option<ApiOracleEvent>,
+    error: Option<String>,
+}
+
+impl ApiBatchResult {
+    fn new(uuid: String, result: Result<StoredEvent, SibylsError>) -> Self {
+        match result {
+            Ok(event) => ApiBatchResult {
+                uuid,
+                status: 200,
+                event: Some(parse_database_entry(event)),
+                error: None,
+            },
+            Err(err) => ApiBatchResult::failed(uuid, &err),
+        }
+    }
+
+    fn failed(uuid: String, err: &SibylsError) -> Self {
+        ApiBatchResult {
+            uuid,
+            status: actix_web::ResponseError::status_code(err).as_u16(),
+            event: None,
+            error: Some(err.to_string()),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct ApiBatchResults {
+    results: Vec<ApiBatchResult>,
+}
+
+/// Settings of the Suredbits compatibility API, served under `/v2` when enabled.
+#[derive(Clone)]
+struct SuredbitsApi {
+    oracle_name: String,
+}
+
+/// An event in the shape of Suredbits' oracle explorer, with Suredbits encodings.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SbAnnouncementEvent {
+    /// hex-encoded SHA256 of `announcement`
+    id: String,
+    oracle_name: String,
+    description: String,
+    uri: Option<String>,
+    announcement: String,
+    attestations: Option<String>,
+    outcome: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SbOracleName {
+    pubkey: String,
+    oracle_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct VerifyRequest {
+    /// hex-encoded `oracle_announcement` TLV
+    announcement: String,
+    /// hex-encoded `oracle_attestation` TLV, verified against `announcement` if given
+    attestation: Option<String>,
+}
+
+#[derive(Serialize)]
+struct VerifyResponse {
+    valid: bool,
+    event_id: String,
+    outcomes: Option<Vec<String>>,
+    error: Option<String>,
 }
 
-fn parse_database_entry(event: IVec) -> ApiOracleEvent {
-    let event: DbValue = serde_json::from_str(&String::from_utf8_lossy(&event)).unwrap();
+#[derive(Serialize)]
+struct ApiOracleKey {
+    public_key: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    activated_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    retired_at: Option<OffsetDateTime>,
+    active: bool,
+}
+
+#[derive(Serialize)]
+struct ApiAssetPair {
+    asset_pair: AssetPair,
+    event_descriptor: EventDescriptor,
+    out_of_range_policy: OutOfRangePolicy,
+}
 
-    let mut announcement_cursor = Cursor::new(&event.3);
+#[derive(Serialize)]
+struct ApiOracleInfo {
+    public_key: String,
+    asset_pairs: Vec<ApiAssetPair>,
+    oracle_config: OracleConfig,
+    version: &'static str,
+}
+
+fn parse_database_entry(event: StoredEvent) -> ApiOracleEvent {
+    let mut announcement_cursor = Cursor::new(&event.announcement);
     let decoded_announcement =
         <dlc_messages::oracle_msgs::OracleAnnouncement as lightning::util::ser::Readable>::read(
             &mut announcement_cursor,
@@ -108,7 +282,7 @@ fn parse_database_entry(event: IVec) -> ApiOracleEvent {
         .unwrap();
     let decoded_ann_json = format!("{:?}", decoded_announcement);
 
-    let db_att = event.4.clone();
+    let db_att = event.attestation.clone();
     let decoded_att_json = match db_att {
         None => None,
         Some(att_vec) => {
@@ -124,304 +298,596 @@ fn parse_database_entry(event: IVec) -> ApiOracleEvent {
 
     ApiOracleEvent {
         event_id: decoded_announcement.oracle_event.event_id.clone(),
-        uuid: event.6,
-        suredbits_announcement: event.1.encode_hex::<String>(),
+        uuid: event.uuid,
+        suredbits_announcement: event.suredbits_announcement.encode_hex::<String>(),
         rust_announcement_json: decoded_ann_json,
-        rust_announcement: event.3.encode_hex::<String>(),
-        suredbits_attestation: event.2.map(|att| att.encode_hex::<String>()),
+        rust_announcement: event.announcement.encode_hex::<String>(),
+        suredbits_attestation: event
+            .suredbits_attestation
+            .map(|att| att.encode_hex::<String>()),
         rust_attestation_json: decoded_att_json,
-        rust_attestation: event.4.map(|att| att.encode_hex::<String>()),
-        maturation: decoded_announcement
-            .oracle_event
-            .event_maturity_epoch
-            .to_string(),
-        outcome: event.5,
+        rust_attestation: event.attestation.map(|att| att.encode_hex::<String>()),
+        maturation: event.maturation.unix_timestamp().to_string(),
+        outcome: event.outcome,
+        unclamped_outcome: event.unclamped_outcome,
+        metadata: event.metadata,
     }
 }
 
-pub fn build_announcement(
-    keypair: &KeyPair,
-    secp: &Secp256k1<All>,
-    maturation: OffsetDateTime,
-    event_id: String,
-) -> Result<(Announcement, Vec<[u8; 32]>), secp256k1_zkp::UpstreamError> {
-    let mut rng = rand::thread_rng();
-    let num_digits = 14u16;
-    let mut sk_nonces = Vec::with_capacity(num_digits.into());
-    let mut nonces = Vec::with_capacity(num_digits.into());
-    for _ in 0..num_digits {
-        let mut sk_nonce = [0u8; 32];
-        rng.fill_bytes(&mut sk_nonce);
-        let oracle_r_kp = secp256k1_zkp::KeyPair::from_seckey_slice(secp, &sk_nonce)?;
-        let nonce = SchnorrPublicKey::from_keypair(&oracle_r_kp);
-        sk_nonces.push(sk_nonce);
-        nonces.push(nonce);
-    }
-
-    let event_descriptor = EventDescriptor {
-        base: 2,
-        is_signed: false,
-        unit: "BTCUSD".to_string(),
-        precision: 0,
-        num_digits,
+fn suredbits_announcement_event(event: StoredEvent, oracle_name: &str) -> SbAnnouncementEvent {
+    // the attested outcome of enum events is only part of their attestation
+    let outcome = match &event.event_descriptor {
+        OracleEventDescriptor::DigitDecompositionEvent(_) => {
+            event.outcome.map(|outcome| outcome.to_string())
+        }
+        OracleEventDescriptor::EnumEvent(_) => event
+            .attestation
+            .as_deref()
+            .and_then(|attestation| decode_attestation(attestation).ok())
+            .and_then(|attestation| attestation.outcomes.into_iter().next()),
     };
+    SbAnnouncementEvent {
+        id: sha256::Hash::hash(&event.suredbits_announcement)
+            .into_inner()
+            .encode_hex::<String>(),
+        oracle_name: oracle_name.to_string(),
+        description: format!("{} {}", event.asset_pair, event.uuid),
+        uri: None,
+        announcement: event.suredbits_announcement.encode_hex::<String>(),
+        attestations: event
+            .suredbits_attestation
+            .map(|attestation| attestation.encode_hex::<String>()),
+        outcome,
+    }
+}
+
+/// Returns the oracle of `asset_pair`, which may be omitted when a single asset pair is configured.
+fn get_oracle<'a>(
+    oracles: &'a HashMap<AssetPair, Oracle>,
+    asset_pair: Option<&AssetPair>,
+) -> Result<&'a Oracle, SibylsError> {
+    match asset_pair {
+        Some(asset_pair) => oracles
+            .get(asset_pair)
+            .ok_or_else(|| SibylsError::UnrecordedAssetPairError(asset_pair.clone())),
+        None if oracles.len() == 1 => Ok(oracles.values().next().unwrap()),
+        None => Err(SibylsError::MissingAssetPairError),
+    }
+}
+
+/// Returns the page of the events of `oracles` requested by `filters`, in order of asset pair
+/// then uuid, or the reverse order if `reverse` is set.
+///
+/// Events are read from the event stores page by page until the page is full, reading at most
+/// [`MAX_SCANNED_EVENTS`]: the page may then be shorter than requested but still have a next one.
+async fn page_by_event_id(
+    mut oracles: Vec<&Oracle>,
+    filters: &Filters,
+    reverse: bool,
+) -> Result<EventPage, SibylsError> {
+    let matching = EventFilters::parse(filters)?;
+    let page_size = filters.page_size as usize;
+    let cursor = filters
+        .cursor
+        .as_deref()
+        .map(|cursor| {
+            cursor
+                .split_once(CURSOR_SEPARATOR)
+                .ok_or_else(|| SibylsError::InvalidCursorError(cursor.to_string()))
+        })
+        .transpose()?;
+    oracles.sort_by(|a, b| {
+        a.asset_pair_info
+            .asset_pair
+            .as_str()
+            .cmp(b.asset_pair_info.asset_pair.as_str())
+    });
+    if reverse {
+        oracles.reverse();
+    }
 
-    let oracle_event = OracleEvent {
-        nonces,
-        maturation,
-        event_descriptor: event_descriptor.clone(),
-        event_id,
+    let mut events = Vec::new();
+    let mut scanned = 0;
+    for oracle in oracles {
+        let asset_pair = oracle.asset_pair_info.asset_pair.as_str();
+        let mut last = None;
+        if let Some((cursor_asset_pair, uuid)) = cursor {
+            // the events of the asset pairs before the cursor's one were listed already
+            match asset_pair.cmp(cursor_asset_pair) {
+                Ordering::Equal => last = Some(uuid.to_string()),
+                Ordering::Less if !reverse => continue,
+                Ordering::Greater if reverse => continue,
+                _ => {}
+            }
+        }
+        loop {
+            let batch = oracle
+                .get_events_page(last.clone(), page_size, reverse)
+                .await?;
+            if batch.is_empty() {
+                break;
+            }
+            for event in batch {
+                scanned += 1;
+                last = Some(event.uuid.clone());
+                if matching.matches(&event) {
+                    events.push(event);
+                }
+                if events.len() == page_size || scanned == MAX_SCANNED_EVENTS {
+                    return Ok(EventPage {
+                        events,
+                        next_cursor: last
+                            .map(|uuid| format!("{}{}{}", asset_pair, CURSOR_SEPARATOR, uuid)),
+                    });
+                }
+            }
+        }
+    }
+    Ok(EventPage {
+        events,
+        next_cursor: None,
+    })
+}
+
+/// Returns the page of the events of `oracles` requested by `filters`, in order of announcement,
+/// or the reverse order if `reverse` is set, the cursor being the number of events listed before
+/// the page.
+///
+/// Event stores are keyed by uuid only, so this order is not paged in the store: every event of
+/// `oracles` is read and decoded to sort them, whichever page is requested.
+async fn page_by_insertion(
+    oracles: Vec<&Oracle>,
+    filters: &Filters,
+    reverse: bool,
+) -> Result<EventPage, SibylsError> {
+    let matching = EventFilters::parse(filters)?;
+    let page_size = filters.page_size as usize;
+    let start = match filters.cursor.as_deref() {
+        Some(cursor) => cursor
+            .parse::<usize>()
+            .map_err(|_| SibylsError::InvalidCursorError(cursor.to_string()))?,
+        None => 0,
     };
 
-    let ann = Announcement {
-        signature: secp.sign_schnorr(
-            &Message::from_hashed_data::<OracleAnnouncementHash>(&oracle_event.encode()),
-            keypair,
-        ),
-        oracle_pubkey: keypair.public_key(),
-        oracle_event,
+    let mut events = Vec::new();
+    for oracle in oracles {
+        events.extend(
+            oracle
+                .get_events()
+                .await?
+                .into_iter()
+                .filter(|event| matching.matches(event)),
+        );
+    }
+    // events announced before records were versioned have no creation time and come first
+    events.sort_by(|a, b| {
+        (a.created_at, a.maturation, &a.uuid).cmp(&(b.created_at, b.maturation, &b.uuid))
+    });
+    if reverse {
+        events.reverse();
+    }
+
+    let end = start.saturating_add(page_size);
+    Ok(EventPage {
+        next_cursor: (end < events.len()).then(|| end.to_string()),
+        events: events.into_iter().skip(start).take(page_size).collect(),
+    })
+}
+
+/// Returns the id of the API key `request` is authenticated with, as a bearer token, if it is
+/// allowed `scope`.
+fn authorize(
+    api_keys: &ApiKeys,
+    request: &HttpRequest,
+    scope: Scope,
+) -> Result<String, SibylsError> {
+    if api_keys.is_disabled() {
+        return Ok("anonymous".to_string());
+    }
+    let key = request
+        .headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| header.strip_prefix("Bearer "))
+        .ok_or(SibylsError::UnauthorizedError)?;
+    let (id, scopes) = api_keys
+        .authenticate(key.trim())
+        .ok_or(SibylsError::UnauthorizedError)?;
+    if !scopes.contains(&scope) {
+        warn!("API key {} is not allowed to {}", id, scope);
+        return Err(SibylsError::ForbiddenError(id.to_string(), scope));
+    }
+    Ok(id.to_string())
+}
+
+/// Returns the event descriptor of an event of `oracle`: an enum event if `outcomes` are given,
+/// otherwise the asset pair's event descriptor with the given `overrides`.
+fn requested_event_descriptor(
+    oracle: &Oracle,
+    outcomes: Option<Vec<String>>,
+    overrides: EventDescriptorOverrides,
+) -> OracleEventDescriptor {
+    match outcomes {
+        Some(outcomes) => EnumEventDescriptor { outcomes }.into(),
+        None => {
+            let mut event_descriptor = oracle.asset_pair_info.event_descriptor.clone();
+            if let Some(base) = overrides.base {
+                event_descriptor.base = base;
+            }
+            if let Some(is_signed) = overrides.is_signed {
+                event_descriptor.is_signed = is_signed;
+            }
+            if let Some(unit) = overrides.unit {
+                event_descriptor.unit = unit;
+            }
+            if let Some(precision) = overrides.precision {
+                event_descriptor.precision = precision;
+            }
+            if let Some(num_digits) = overrides.num_digits {
+                event_descriptor.num_digits = num_digits;
+            }
+            event_descriptor.into()
+        }
+    }
+}
+
+/// Attests event `uuid` of `oracle` to `outcome`, or to the price reported by its pricefeeds if
+/// `None`.
+async fn attest_event(
+    oracle: &Oracle,
+    uuid: String,
+    outcome: Option<String>,
+    early_attestation_reason: Option<String>,
+    idempotency_key: Option<String>,
+) -> Result<StoredEvent, SibylsError> {
+    let result = match outcome {
+        Some(outcome) => {
+            oracle
+                .attest(
+                    uuid.clone(),
+                    outcome,
+                    early_attestation_reason,
+                    idempotency_key,
+                )
+                .await
+        }
+        None => {
+            oracle
+                .attest_with_pricefeeds(uuid.clone(), idempotency_key)
+                .await
+        }
     };
-    Ok((ann, sk_nonces))
+    match result {
+        Ok(event) => Ok(event),
+        Err(OracleError::EventNotFoundError) => Err(SibylsError::OracleEventNotFoundError(uuid)),
+        Err(err) => Err(SibylsError::OracleDatabaseError(err)),
+    }
 }
 
-pub fn build_attestation(
-    outstanding_sk_nonces: Vec<[u8; 32]>,
-    keypair: &KeyPair,
-    secp: &Secp256k1<All>,
-    outcomes: Vec<String>,
-) -> Attestation {
-    let signatures = outcomes
-        .iter()
-        .zip(outstanding_sk_nonces.iter())
-        .map(|(outcome, outstanding_sk_nonce)| {
-            sign_schnorr_with_nonce(
-                secp,
-                &Message::from_hashed_data::<sha256::Hash>(
-                    // &Message::from_hashed_data::<secp256k1_zkp_5::bitcoin_hashes::sha256::Hash>(
-                    outcome.as_bytes(),
-                ),
-                keypair,
-                outstanding_sk_nonce,
-            )
+/// Parses the asset pair of a JSON request body.
+fn parse_asset_pair(asset_pair: &Option<String>) -> Result<Option<AssetPair>, SibylsError> {
+    asset_pair
+        .as_deref()
+        .map(|asset_pair| {
+            asset_pair
+                .parse()
+                .map_err(SibylsError::InvalidAssetPairError)
         })
-        .collect::<Vec<_>>();
-    Attestation {
-        oracle_pubkey: keypair.public_key(),
-        signatures,
-        outcomes,
-    }
-}
-
-fn sign_schnorr_with_nonce<S: Signing>(
-    secp: &Secp256k1<S>,
-    msg: &Message,
-    keypair: &KeyPair,
-    nonce: &[u8; 32],
-) -> SchnorrSignature {
-    unsafe {
-        let mut sig = [0u8; SCHNORR_SIGNATURE_SIZE];
-        let nonce_params =
-            SchnorrSigExtraParams::new(Some(constant_nonce_fn), nonce.as_c_ptr() as *const c_void);
-        assert_eq!(
-            1,
-            secp256k1_sys::secp256k1_schnorrsig_sign_custom(
-                *secp.ctx(),
-                sig.as_mut_c_ptr(),
-                msg.as_c_ptr(),
-                msg.len(),
-                keypair.as_ptr(),
-                &nonce_params as *const SchnorrSigExtraParams
-            )
-        );
+        .transpose()
+}
 
-        SchnorrSignature::from_slice(&sig).unwrap()
+// responses of the GET routes superseded by the POST routes
+fn deprecated_response(successor: &str) -> actix_web::HttpResponseBuilder {
+    let mut response = HttpResponse::Ok();
+    response
+        .insert_header(("Deprecation", "true"))
+        .insert_header((
+            actix_web::http::header::LINK,
+            format!("<{}>; rel=\"successor-version\"", successor),
+        ));
+    response
+}
+
+/// Returns the oracle of the event requested by `body`, and the event to create.
+fn event_to_create<'a>(
+    oracles: &'a HashMap<AssetPair, Oracle>,
+    body: CreateEventRequest,
+) -> Result<(&'a Oracle, EventToCreate), SibylsError> {
+    let maturation = OffsetDateTime::parse(&body.maturation, &Rfc3339)?;
+    if body.outcomes.is_some() && body.event_descriptor.is_some() {
+        return Err(SibylsError::InvalidRequestError(
+            "enum events have no event descriptor to override".to_string(),
+        ));
     }
+
+    let oracle = get_oracle(oracles, parse_asset_pair(&body.asset_pair)?.as_ref())?;
+    let event_descriptor = requested_event_descriptor(
+        oracle,
+        body.outcomes,
+        body.event_descriptor.unwrap_or_default(),
+    );
+    Ok((
+        oracle,
+        EventToCreate {
+            uuid: body.uuid,
+            maturation,
+            event_descriptor: Some(event_descriptor),
+            metadata: body.metadata,
+            idempotency_key: body.idempotency_key,
+        },
+    ))
 }
 
-#[get("/create_event/{uuid}")]
-async fn create_event(
+#[post("/events")]
+async fn post_event(
     oracles: web::Data<HashMap<AssetPair, Oracle>>,
-    filters: web::Query<Filters>,
-    path: web::Path<String>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    body: web::Json<CreateEventRequest>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
-    info!("GET /create_event/{}: {:#?}", path, filters);
-    let uuid = path.to_string();
-    let maturation = OffsetDateTime::parse(&filters.maturation, &Rfc3339)
-        .map_err(SibylsError::DatetimeParseError)?;
+    let caller = authorize(&api_keys, &request, Scope::CreateEvent)?;
+    info!("POST /events by {}: {:#?}", caller, body);
+    let (oracle, event) = event_to_create(&oracles, body.into_inner())?;
+
+    let event = oracle
+        .create_event(
+            event.uuid,
+            event.maturation,
+            event.event_descriptor,
+            event.metadata,
+            event.idempotency_key,
+        )
+        .await
+        .map_err(SibylsError::OracleDatabaseError)?;
 
+    Ok(HttpResponse::Ok().json(parse_database_entry(event)))
+}
+
+#[post("/events/{uuid}/attestation")]
+async fn post_attestation(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    body: web::Json<AttestationRequest>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    let caller = authorize(&api_keys, &request, Scope::Attest)?;
     info!(
-        "Creating event for uuid:{} and maturation_time :{}",
-        uuid, maturation
+        "POST /events/{}/attestation by {}: {:#?}",
+        path, caller, body
     );
+    let body = body.into_inner();
+    let oracle = get_oracle(&oracles, parse_asset_pair(&body.asset_pair)?.as_ref())?;
 
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
-        Some(val) => val,
-    };
-
-    let (announcement_obj, outstanding_sk_nonces) = build_announcement(
-        oracle.get_keypair(),
-        oracle.get_secp(),
-        maturation,
-        uuid.clone(),
+    let event = attest_event(
+        oracle,
+        path.into_inner(),
+        body.outcome.map(|outcome| outcome.to_string()),
+        body.early_attestation_reason,
+        body.idempotency_key,
     )
-    .unwrap();
+    .await?;
 
-    let db_value = DbValue(
-        Some(outstanding_sk_nonces),
-        announcement_obj.suredbits_encode(),
-        None,
-        announcement_obj.encode(),
-        None,
-        None,
-        uuid.clone(),
-    );
+    Ok(HttpResponse::Ok().json(parse_database_entry(event)))
+}
 
-    let new_event = serde_json::to_string(&db_value)?.into_bytes();
-    info!("Inserting new event ...[uuid: {}]", uuid.clone());
-    if oracle.event_handler.storage_api.is_some() {
-        oracle
-            .event_handler
-            .storage_api
-            .as_ref()
-            .unwrap()
-            .insert(uuid.clone(), new_event.clone())
-            .await
-            .unwrap();
-    } else {
-        oracle
-            .event_handler
-            .sled_db
-            .as_ref()
-            .unwrap()
-            .insert(uuid.clone().into_bytes(), new_event.clone())
-            .unwrap();
+fn check_batch_size(size: usize) -> Result<(), SibylsError> {
+    if size == 0 || size > MAX_BATCH_SIZE {
+        return Err(SibylsError::InvalidBatchSizeError(size));
     }
+    Ok(())
+}
 
-    Ok(HttpResponse::Ok().json(parse_database_entry(new_event.into())))
+/// Collects the results of a batch, which were computed per asset pair, in request order.
+fn batch_results(mut results: Vec<(usize, ApiBatchResult)>) -> ApiBatchResults {
+    results.sort_by_key(|(index, _)| *index);
+    ApiBatchResults {
+        results: results.into_iter().map(|(_, result)| result).collect(),
+    }
 }
 
-#[get("/attest/{uuid}")]
-async fn attest(
+#[post("/batch/events")]
+async fn post_events(
     oracles: web::Data<HashMap<AssetPair, Oracle>>,
-    filters: web::Query<Filters>,
-    path: web::Path<String>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    body: web::Json<BatchCreateEventsRequest>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
-    info!("GET /attest/{}: {:#?}", path, filters);
-    let uuid = path.to_string();
-    let outcome = &filters.outcome.unwrap();
+    let caller = authorize(&api_keys, &request, Scope::CreateEvent)?;
+    info!("POST /batch/events by {}: {:#?}", caller, body);
+    let events = body.into_inner().events;
+    check_batch_size(events.len())?;
 
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
-        Some(val) => val,
-    };
+    let mut results = Vec::with_capacity(events.len());
+    // events are created and stored together per asset pair
+    let mut batches: HashMap<&AssetPair, (&Oracle, Vec<usize>, Vec<EventToCreate>)> =
+        HashMap::new();
+    for (index, body) in events.into_iter().enumerate() {
+        let uuid = body.uuid.clone();
+        match event_to_create(&oracles, body) {
+            Ok((oracle, event)) => {
+                let batch = batches
+                    .entry(&oracle.asset_pair_info.asset_pair)
+                    .or_insert_with(|| (oracle, Vec::new(), Vec::new()));
+                batch.1.push(index);
+                batch.2.push(event);
+            }
+            Err(err) => results.push((index, ApiBatchResult::failed(uuid, &err))),
+        }
+    }
 
-    if oracle.event_handler.is_empty() {
-        info!("no oracle events found");
-        return Err(SibylsError::OracleEventNotFoundError(uuid).into());
+    for (oracle, indexes, events) in batches.into_values() {
+        let uuids: Vec<String> = events.iter().map(|event| event.uuid.clone()).collect();
+        match oracle.create_events(events).await {
+            Ok(created) => results.extend(indexes.into_iter().zip(uuids).zip(created).map(
+                |((index, uuid), result)| {
+                    (
+                        index,
+                        ApiBatchResult::new(uuid, result.map_err(SibylsError::OracleDatabaseError)),
+                    )
+                },
+            )),
+            Err(err) => {
+                let err = SibylsError::OracleDatabaseError(err);
+                results.extend(
+                    indexes
+                        .into_iter()
+                        .zip(uuids)
+                        .map(|(index, uuid)| (index, ApiBatchResult::failed(uuid, &err))),
+                );
+            }
+        }
     }
 
-    info!("retrieving oracle event with maturation {}", path);
-    let mut event: DbValue;
-    if oracle.event_handler.storage_api.is_some() {
-        let event_vec = match oracle
-            .event_handler
-            .storage_api
-            .as_ref()
-            .unwrap()
-            .get(uuid.clone())
-            .await
-            .map_err(SibylsError::OracleDatabaseError)?
-        {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(uuid).into()),
-        };
-        event = serde_json::from_str(&String::from_utf8_lossy(&event_vec)).unwrap();
-    } else {
-        let event_ivec = match oracle
-            .event_handler
-            .sled_db
-            .as_ref()
-            .unwrap()
-            .get(path.as_bytes())
-            .map_err(SibylsError::DatabaseError)?
+    Ok(HttpResponse::Ok().json(batch_results(results)))
+}
+
+#[post("/batch/attestations")]
+async fn post_attestations(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    body: web::Json<BatchAttestationRequest>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    let caller = authorize(&api_keys, &request, Scope::Attest)?;
+    info!("POST /batch/attestations by {}: {:#?}", caller, body);
+    let attestations = body.into_inner().attestations;
+    check_batch_size(attestations.len())?;
+
+    let mut results = Vec::with_capacity(attestations.len());
+    // events are attested and stored together per asset pair
+    let mut batches: HashMap<&AssetPair, (&Oracle, Vec<usize>, Vec<EventToAttest>)> =
+        HashMap::new();
+    for (index, body) in attestations.into_iter().enumerate() {
+        let oracle = match parse_asset_pair(&body.asset_pair)
+            .and_then(|asset_pair| get_oracle(&oracles, asset_pair.as_ref()))
         {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(uuid).into()),
+            Ok(oracle) => oracle,
+            Err(err) => {
+                results.push((index, ApiBatchResult::failed(body.uuid, &err)));
+                continue;
+            }
         };
-        event = serde_json::from_str(&String::from_utf8_lossy(&event_ivec)).unwrap();
+        let batch = batches
+            .entry(&oracle.asset_pair_info.asset_pair)
+            .or_insert_with(|| (oracle, Vec::new(), Vec::new()));
+        batch.1.push(index);
+        batch.2.push(EventToAttest {
+            uuid: body.uuid,
+            outcome: body.outcome.map(|outcome| outcome.to_string()),
+            early_attestation_reason: body.early_attestation_reason,
+            idempotency_key: body.idempotency_key,
+        });
     }
 
-    let outstanding_sk_nonces = event.clone().0.unwrap();
-
-    let mut announcement_cursor = Cursor::new(&event.3);
-    let announcement =
-        <dlc_messages::oracle_msgs::OracleAnnouncement as lightning::util::ser::Readable>::read(
-            &mut announcement_cursor,
-        )
-        .unwrap();
-
-    let num_digits_to_sign = match announcement.oracle_event.event_descriptor {
-        dlc_messages::oracle_msgs::EventDescriptor::DigitDecompositionEvent(e) => e.nb_digits,
-        _ => {
-            return Err(SibylsError::OracleEventNotFoundError(
-                "Got an unexpected EventDescriptor type!".to_string(),
-            )
-            .into())
+    for (oracle, indexes, attestations) in batches.into_values() {
+        let uuids: Vec<String> = attestations
+            .iter()
+            .map(|attestation| attestation.uuid.clone())
+            .collect();
+        match oracle.attest_events(attestations).await {
+            Ok(attested) => results.extend(indexes.into_iter().zip(uuids).zip(attested).map(
+                |((index, uuid), result)| {
+                    let result = result.map_err(|err| match err {
+                        OracleError::EventNotFoundError => {
+                            SibylsError::OracleEventNotFoundError(uuid.clone())
+                        }
+                        err => SibylsError::OracleDatabaseError(err),
+                    });
+                    (index, ApiBatchResult::new(uuid, result))
+                },
+            )),
+            Err(err) => {
+                let err = SibylsError::OracleDatabaseError(err);
+                results.extend(
+                    indexes
+                        .into_iter()
+                        .zip(uuids)
+                        .map(|(index, uuid)| (index, ApiBatchResult::failed(uuid, &err))),
+                );
+            }
         }
-    };
+    }
 
-    // Here, we take the outcome of the DLC (0-10000), break it down into binary, break it into a vec of characters
-    let outcomes = format!("{:0width$b}", outcome, width = num_digits_to_sign as usize)
-        .chars()
-        .map(|char| char.to_string())
-        .collect::<Vec<_>>();
+    Ok(HttpResponse::Ok().json(batch_results(results)))
+}
+
+/// Deprecated alias of `POST /v1/events`, with the request in the query string.
+#[get("/create_event/{uuid}")]
+async fn create_event(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    filters: web::Query<Filters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    let caller = authorize(&api_keys, &request, Scope::CreateEvent)?;
+    info!("GET /create_event/{} by {}: {:#?}", path, caller, filters);
+    let uuid = path.to_string();
+    let maturation = OffsetDateTime::parse(&filters.maturation, &Rfc3339)
+        .map_err(SibylsError::DatetimeParseError)?;
 
-    let attestation = build_attestation(
-        outstanding_sk_nonces,
-        oracle.get_keypair(),
-        &oracle.get_secp(),
-        outcomes,
+    info!(
+        "Creating event for uuid:{} and maturation_time :{}",
+        uuid, maturation
     );
 
-    event.2 = Some(attestation.suredbits_encode());
-    event.5 = Some(*outcome);
-    event.4 = Some(attestation.encode());
+    let oracle = get_oracle(&oracles, filters.asset_pair.as_ref())?;
 
-    info!(
-        "attesting with maturation {} and attestation {:#?}",
-        path, attestation
+    // a comma separated list of outcomes announces an enum event, otherwise any event descriptor
+    // field given in the query overrides the asset pair's
+    let filters = filters.into_inner();
+    let event_descriptor = requested_event_descriptor(
+        oracle,
+        filters
+            .outcomes
+            .map(|outcomes| outcomes.split(',').map(str::to_string).collect()),
+        EventDescriptorOverrides {
+            base: filters.base,
+            is_signed: filters.is_signed,
+            unit: filters.unit,
+            precision: filters.precision,
+            num_digits: filters.num_digits,
+        },
     );
 
-    let new_event = serde_json::to_string(&event)?.into_bytes();
+    let event = oracle
+        .create_event(uuid, maturation, Some(event_descriptor), None, None)
+        .await
+        .map_err(SibylsError::OracleDatabaseError)?;
 
-    if oracle.event_handler.storage_api.is_some() {
-        let _insert_event = match oracle
-            .event_handler
-            .storage_api
-            .as_ref()
-            .unwrap()
-            .insert(path.clone(), new_event.clone())
-            .await
-            .map_err(SibylsError::OracleDatabaseError)?
-        {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(uuid).into()),
-        };
-    } else {
-        let _insert_event = match oracle
-            .event_handler
-            .sled_db
-            .as_ref()
-            .unwrap()
-            .insert(path.clone().as_bytes(), new_event.clone())
-            .map_err(SibylsError::DatabaseError)?
-        {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(uuid).into()),
-        };
-    }
-    Ok(HttpResponse::Ok().json(parse_database_entry(new_event.into())))
+    Ok(deprecated_response("/v1/events").json(parse_database_entry(event)))
+}
+
+/// Deprecated alias of `POST /v1/events/{uuid}/attestation`, with the request in the query string.
+#[get("/attest/{uuid}")]
+async fn attest(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    api_keys: web::Data<ApiKeys>,
+    request: HttpRequest,
+    filters: web::Query<Filters>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    let caller = authorize(&api_keys, &request, Scope::Attest)?;
+    info!("GET /attest/{} by {}: {:#?}", path, caller, filters);
+    let uuid = path.to_string();
+
+    let oracle = get_oracle(&oracles, filters.asset_pair.as_ref())?;
+
+    // without an outcome, the oracle attests to the price reported by its pricefeeds
+    let event = attest_event(
+        oracle,
+        uuid.clone(),
+        filters.outcome.clone(),
+        filters.early_attestation_reason.clone(),
+        None,
+    )
+    .await?;
+
+    Ok(
+        deprecated_response(&format!("/v1/events/{}/attestation", uuid))
+            .json(parse_database_entry(event)),
+    )
 }
 
 #[get("/announcements")]
@@ -430,42 +896,45 @@ async fn announcements(
     filters: web::Query<Filters>,
 ) -> actix_web::Result<HttpResponse, actix_web::Error> {
     info!("GET /announcements: {:#?}", filters);
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
-        Some(val) => val,
+    // without an asset pair, events of all asset pairs are listed
+    let selected = match &filters.asset_pair {
+        Some(_) => vec![get_oracle(&oracles, filters.asset_pair.as_ref())?],
+        None => oracles.values().collect(),
     };
 
-    if oracle.event_handler.is_empty() {
-        info!("no oracle events found");
-        return Ok(HttpResponse::Ok().json(Vec::<ApiOracleEvent>::new()));
+    if filters.page_size == 0 || filters.page_size > MAX_PAGE_SIZE {
+        return Err(SibylsError::InvalidPageSizeError(filters.page_size).into());
     }
-    if oracle.event_handler.storage_api.is_some() {
-        return Ok(HttpResponse::Ok().json(
-            oracle
-                .event_handler
-                .storage_api
-                .as_ref()
-                .unwrap()
-                .get_all()
-                .await
-                .unwrap()
-                .unwrap()
-                .iter()
-                .map(|result| parse_database_entry(result.clone().1.into()))
-                .collect::<Vec<_>>(),
-        ));
-    } else {
-        return Ok(HttpResponse::Ok().json(
-            oracle
-                .event_handler
-                .sled_db
-                .as_ref()
-                .unwrap()
-                .iter()
-                .map(|result| parse_database_entry(result.unwrap().1))
-                .collect::<Vec<_>>(),
-        ));
+    if filters.page.is_some() {
+        return Err(SibylsError::PageNumberError.into());
+    }
+
+    // counted by the event stores, so without reading the events
+    let mut total = 0;
+    for oracle in &selected {
+        total += oracle
+            .count_events()
+            .await
+            .map_err(SibylsError::OracleDatabaseError)?;
     }
+
+    let page = match filters.sort_by {
+        SortOrder::EventId => page_by_event_id(selected, &filters, false).await?,
+        SortOrder::ReverseEventId => page_by_event_id(selected, &filters, true).await?,
+        SortOrder::Insertion => page_by_insertion(selected, &filters, false).await?,
+        SortOrder::ReverseInsertion => page_by_insertion(selected, &filters, true).await?,
+    };
+    let mut response = HttpResponse::Ok();
+    if let Some(cursor) = page.next_cursor {
+        response.insert_header(("X-Next-Cursor", cursor));
+    }
+    response.insert_header(("X-Total-Count", total.to_string()));
+    Ok(response.json(
+        page.events
+            .into_iter()
+            .map(parse_database_entry)
+            .collect::<Vec<_>>(),
+    ))
 }
 
 #[get("/announcement/{uuid}")]
@@ -477,62 +946,209 @@ async fn get_announcement(
     info!("GET /announcement/{}: {:#?}", path, filters);
     let uuid = path.to_string();
 
-    let oracle = match oracles.get(&filters.asset_pair) {
-        None => return Err(SibylsError::UnrecordedAssetPairError(filters.asset_pair).into()),
+    let oracle = get_oracle(&oracles, filters.asset_pair.as_ref())?;
+
+    info!("retrieving oracle event with uuid {}", uuid);
+    let event = match oracle
+        .get_event(uuid)
+        .await
+        .map_err(SibylsError::OracleDatabaseError)?
+    {
         Some(val) => val,
+        None => return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into()),
     };
+    Ok(HttpResponse::Ok().json(parse_database_entry(event)))
+}
+
+/// Decodes a hex-encoded TLV `message` with `decode`.
+fn decode_hex_message<T>(
+    message: &str,
+    decode: fn(&[u8]) -> Result<T, OracleError>,
+) -> Result<T, SibylsError> {
+    let message =
+        Vec::from_hex(message).map_err(|e| SibylsError::InvalidMessageError(e.to_string()))?;
+    decode(&message).map_err(|e| SibylsError::InvalidMessageError(e.to_string()))
+}
 
-    if oracle.event_handler.is_empty() {
-        info!("no oracle events found");
-        return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into());
+/// Returns the events of all asset pairs, most recent first.
+async fn get_all_events(
+    oracles: &HashMap<AssetPair, Oracle>,
+) -> Result<Vec<StoredEvent>, SibylsError> {
+    let mut events = Vec::new();
+    for oracle in oracles.values() {
+        events.extend(oracle.get_events().await?);
     }
+    events.sort_by(|a, b| {
+        (b.created_at, b.maturation, &b.uuid).cmp(&(a.created_at, a.maturation, &a.uuid))
+    });
+    Ok(events)
+}
 
-    info!("retrieving oracle event with uuid {}", uuid);
-    if oracle.event_handler.storage_api.is_some() {
-        let event = match oracle
-            .event_handler
-            .storage_api
-            .as_ref()
-            .unwrap()
-            .get(uuid.clone())
-            .await
-            .map_err(SibylsError::OracleDatabaseError)?
-        {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into()),
-        };
-        Ok(HttpResponse::Ok().json(parse_database_entry(event.into())))
-    } else {
-        let event = match oracle
-            .event_handler
-            .sled_db
-            .as_ref()
-            .unwrap()
-            .get(uuid.as_bytes())
-            .map_err(SibylsError::DatabaseError)?
-        {
-            Some(val) => val,
-            None => return Err(SibylsError::OracleEventNotFoundError(path.to_string()).into()),
-        };
-        Ok(HttpResponse::Ok().json(parse_database_entry(event)))
+#[get("/announcements")]
+async fn suredbits_announcements(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    suredbits_api: web::Data<SuredbitsApi>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /v2/announcements");
+    let events: Vec<SbAnnouncementEvent> = get_all_events(&oracles)
+        .await?
+        .into_iter()
+        .map(|event| suredbits_announcement_event(event, &suredbits_api.oracle_name))
+        .collect();
+    Ok(HttpResponse::Ok().json(events))
+}
+
+#[get("/announcements/{announcement_hash}")]
+async fn suredbits_announcement(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    suredbits_api: web::Data<SuredbitsApi>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /v2/announcements/{}", path);
+    let announcement_hash = path.to_lowercase();
+    let event = get_all_events(&oracles)
+        .await?
+        .into_iter()
+        .map(|event| suredbits_announcement_event(event, &suredbits_api.oracle_name))
+        .find(|event| event.id == announcement_hash)
+        .ok_or_else(|| SibylsError::OracleEventNotFoundError(path.to_string()))?;
+    Ok(HttpResponse::Ok().json(event))
+}
+
+#[get("/oracle/{pubkey}")]
+async fn suredbits_oracle(
+    signer: web::Data<dyn Signer>,
+    suredbits_api: web::Data<SuredbitsApi>,
+    path: web::Path<String>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /v2/oracle/{}", path);
+    let known = signer_keys(signer)
+        .await?
+        .iter()
+        .any(|key| key.public_key.to_string() == path.to_lowercase());
+    if !known {
+        return Err(SibylsError::UnknownOracleError(path.to_string()).into());
     }
+    Ok(HttpResponse::Ok().json(SbOracleName {
+        pubkey: path.to_lowercase(),
+        oracle_name: suredbits_api.oracle_name.clone(),
+    }))
+}
+
+#[post("/verify")]
+async fn verify(
+    request: web::Json<VerifyRequest>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("POST /verify: {:#?}", request);
+    let announcement = decode_hex_message(&request.announcement, decode_announcement)?;
+    let attestation = request
+        .attestation
+        .as_deref()
+        .map(|attestation| decode_hex_message(attestation, decode_attestation))
+        .transpose()?;
+
+    let secp = Secp256k1::verification_only();
+    let result = match &attestation {
+        Some(attestation) => verify_attestation(&secp, &announcement, attestation),
+        None => verify_announcement(&secp, &announcement),
+    };
+    Ok(HttpResponse::Ok().json(VerifyResponse {
+        valid: result.is_ok(),
+        event_id: announcement.oracle_event.event_id,
+        outcomes: attestation.map(|attestation| attestation.outcomes),
+        error: result.err().map(|e| e.to_string()),
+    }))
+}
+
+/// Returns the keys of `signer`, which a remote signer is asked off the async runtime.
+async fn signer_keys(signer: web::Data<dyn Signer>) -> Result<Vec<SignerKey>, actix_web::Error> {
+    let signer = signer.into_inner();
+    Ok(web::block(move || signer.keys()).await?)
+}
+
+/// Returns the active key of `signer`, see [`signer_keys`].
+async fn signer_public_key(
+    signer: web::Data<dyn Signer>,
+) -> Result<SchnorrPublicKey, actix_web::Error> {
+    let signer = signer.into_inner();
+    Ok(web::block(move || signer.public_key()).await?)
 }
 
 #[get("/publickey")]
-async fn publickey() -> actix_web::Result<HttpResponse, actix_web::Error> {
+async fn publickey(
+    signer: web::Data<dyn Signer>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
     info!("GET /publickey");
-    let secp = Secp256k1::new();
-    let keypair = get_or_generate_keypair(&secp, Some(PathBuf::from("config/secret.key"))).await;
-    Ok(HttpResponse::Ok().json(keypair.public_key().serialize().encode_hex::<String>()))
+    let public_key = signer_public_key(signer).await?;
+    Ok(HttpResponse::Ok().json(public_key.serialize().encode_hex::<String>()))
+}
+
+#[get("/config")]
+async fn config(
+    oracle_config: web::Data<OracleConfig>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /config");
+    Ok(HttpResponse::Ok().json(oracle_config.get_ref()))
+}
+
+#[get("/info")]
+async fn oracle_info(
+    oracles: web::Data<HashMap<AssetPair, Oracle>>,
+    signer: web::Data<dyn Signer>,
+    oracle_config: web::Data<OracleConfig>,
+) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /info");
+    let mut asset_pairs = oracles
+        .values()
+        .map(|oracle| ApiAssetPair {
+            asset_pair: oracle.asset_pair_info.asset_pair.clone(),
+            event_descriptor: oracle.asset_pair_info.event_descriptor.clone(),
+            out_of_range_policy: oracle.asset_pair_info.out_of_range_policy,
+        })
+        .collect::<Vec<_>>();
+    asset_pairs.sort_by(|a, b| a.asset_pair.as_str().cmp(b.asset_pair.as_str()));
+    Ok(HttpResponse::Ok().json(ApiOracleInfo {
+        public_key: signer_public_key(signer)
+            .await?
+            .serialize()
+            .encode_hex::<String>(),
+        asset_pairs,
+        oracle_config: *oracle_config.get_ref(),
+        version: env!("CARGO_PKG_VERSION"),
+    }))
+}
+
+#[get("/keys")]
+async fn keys(signer: web::Data<dyn Signer>) -> actix_web::Result<HttpResponse, actix_web::Error> {
+    info!("GET /keys");
+    Ok(HttpResponse::Ok().json(
+        signer_keys(signer)
+            .await?
+            .iter()
+            .map(|key| ApiOracleKey {
+                public_key: key.public_key.to_string(),
+                activated_at: key.activated_at,
+                retired_at: key.retired_at,
+                active: key.retired_at.is_none(),
+            })
+            .collect::<Vec<_>>(),
+    ))
 }
 
 #[derive(Parser)]
 /// Simple DLC oracle implementation
 struct Args {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// Optional private key file; if not provided, one is generated
     #[clap(short, long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
     secret_key_file: Option<std::path::PathBuf>,
 
+    /// Optional socket of a signer process holding the oracle keys, see the signer command; if not provided, ORACLE_SIGNER_SOCKET is used, else the oracle holds its keys
+    #[clap(long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    signer_socket: Option<std::path::PathBuf>,
+
     /// Optional asset pair config file; if not provided, it is assumed to exist at "config/asset_pair.json"
     #[clap(short, long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
     asset_pair_config_file: Option<std::path::PathBuf>,
@@ -540,20 +1156,69 @@ struct Args {
     /// Optional oracle config file; if not provided, it is assumed to exist at "config/oracle.json"
     #[clap(short, long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
     oracle_config_file: Option<std::path::PathBuf>,
+
+    /// Optional API key config file; if not provided, keys are read from Vault when enabled, else from "config/api_keys.json"
+    #[clap(short = 'k', long, parse(from_os_str), value_name = "FILE", value_hint = clap::ValueHint::FilePath)]
+    api_key_file: Option<std::path::PathBuf>,
 }
 
-#[actix_web::main]
-async fn main() -> anyhow::Result<()> {
-    env_logger::init();
-    let args = Args::parse();
-    let secp = Secp256k1::new();
-    let keypair = get_or_generate_keypair(&secp, args.secret_key_file).await;
-    info!(
-        "oracle keypair successfully generated, pubkey is {}",
-        keypair.public_key().serialize().encode_hex::<String>()
-    );
+/// Reads the oracle config from `oracle_config_file`, or from "config/oracle.json" if not provided.
+fn read_oracle_config(
+    oracle_config_file: Option<std::path::PathBuf>,
+) -> anyhow::Result<OracleConfig> {
+    let oracle_config: OracleConfig = match oracle_config_file {
+        None => {
+            info!("reading oracle config from config/oracle.json");
+            serde_json::from_str(&fs::read_to_string("config/oracle.json")?)?
+        }
+        Some(path) => {
+            info!(
+                "reading oracle config from {}",
+                path.as_os_str().to_string_lossy()
+            );
+            let mut oracle_config = String::new();
+            File::open(path)?.read_to_string(&mut oracle_config)?;
+            serde_json::from_str(&oracle_config)?
+        }
+    };
+    info!("oracle config successfully read: {:#?}", oracle_config);
+    Ok(oracle_config)
+}
+
+/// Returns the signer of the oracle: the signer process listening on `signer_socket`, else on
+/// ORACLE_SIGNER_SOCKET, if any, else one holding the oracle keys, which are generated if the
+/// oracle has none and `generate` is set.
+async fn load_signer(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<std::path::PathBuf>,
+    signer_socket: Option<std::path::PathBuf>,
+    generate: bool,
+) -> anyhow::Result<Arc<dyn Signer>> {
+    let signer_socket =
+        signer_socket.or_else(|| env::var_os("ORACLE_SIGNER_SOCKET").map(PathBuf::from));
+    if let Some(signer_socket) = signer_socket {
+        return Ok(Arc::new(RemoteSigner::connect(signer_socket)?));
+    }
+    let keyring = if generate {
+        get_or_generate_keyring(secp, secret_key_file).await?
+    } else {
+        read_keyring(secp, secret_key_file).await?
+    };
+    Ok(Arc::new(LocalSigner::new(keyring, secp.clone())))
+}
 
-    let asset_pair_infos: Vec<AssetPairInfo> = match args.asset_pair_config_file {
+/// Reads the asset pair and oracle configs, and creates the oracle of each asset pair on
+/// `event_storage`, upgrading its stored events if `migrate` is set. Events of older record
+/// versions are upgraded when read otherwise, but are left as stored.
+async fn load_oracles(
+    secp: &Secp256k1<All>,
+    signer: &Arc<dyn Signer>,
+    event_storage: &EventHandler,
+    asset_pair_config_file: Option<std::path::PathBuf>,
+    oracle_config_file: Option<std::path::PathBuf>,
+    migrate: bool,
+) -> anyhow::Result<(OracleConfig, HashMap<AssetPair, Oracle>)> {
+    let asset_pair_infos: Vec<AssetPairInfo> = match asset_pair_config_file {
         None => {
             info!("reading asset pair config from config/asset_pair.json");
             serde_json::from_str(&fs::read_to_string("config/asset_pair.json")?)?
@@ -573,38 +1238,209 @@ async fn main() -> anyhow::Result<()> {
         asset_pair_infos
     );
 
-    let oracle_config: OracleConfig = match args.oracle_config_file {
-        None => {
-            info!("reading oracle config from config/oracle.json");
-            serde_json::from_str(&fs::read_to_string("config/oracle.json")?)?
+    let oracle_config = read_oracle_config(oracle_config_file)?;
+
+    if asset_pair_infos.is_empty() {
+        anyhow::bail!("no asset pair configured");
+    }
+    validate_asset_pair_infos(&asset_pair_infos).map_err(anyhow::Error::msg)?;
+
+    // setup event databases, with one namespace per asset pair
+    let mut oracles = HashMap::new();
+    for asset_pair_info in asset_pair_infos {
+        let asset_pair = asset_pair_info.asset_pair.clone();
+        let event_handler = event_storage.for_asset_pair(&asset_pair)?;
+
+        // events stored before asset pairs had their own namespace can only be of BTCUSD
+        if asset_pair.as_str() == LEGACY_ASSET_PAIR && migrate {
+            let migrated = event_storage
+                .migrate_unnamespaced_events(&asset_pair)
+                .await?;
+            if migrated > 0 {
+                info!(
+                    "moved {} events to the namespace of {}",
+                    migrated, asset_pair
+                );
+            }
+        } else if asset_pair.as_str() == LEGACY_ASSET_PAIR {
+            let unmigrated = event_storage.get_all().await?.len();
+            if unmigrated > 0 {
+                warn!(
+                    "{} events stored before asset pairs had their own namespace are left out \
+                     until they are moved to the namespace of {}, e.g. by starting the oracle",
+                    unmigrated, asset_pair
+                );
+            }
         }
-        Some(path) => {
+
+        // create oracle
+        info!("creating oracle for {}", asset_pair);
+        let oracle = Oracle::new(
+            oracle_config,
+            asset_pair_info,
+            event_handler,
+            signer.clone(),
+            secp.clone(),
+        )?;
+        if migrate {
+            let upgraded = oracle.migrate_stored_events().await?;
+            if upgraded > 0 {
+                info!("upgraded {} stored events of {}", upgraded, asset_pair);
+            }
+        }
+        oracles.insert(asset_pair, oracle);
+    }
+    Ok((oracle_config, oracles))
+}
+
+#[actix_web::main]
+async fn main() -> anyhow::Result<()> {
+    env_logger::init();
+    let args = Args::parse();
+    let secp = Secp256k1::new();
+    match args.command {
+        None => {}
+        Some(Command::Keygen) => return cli::keygen(&secp, args.secret_key_file).await,
+        Some(Command::ShowPubkey { all }) => {
+            return cli::show_pubkey(&secp, args.secret_key_file, all).await
+        }
+        Some(Command::Signer {
+            socket,
+            db_path,
+            allow_early_attestation,
+            seed,
+        }) => {
+            let oracle_config = read_oracle_config(args.oracle_config_file)?;
+            return cli::signer(
+                &secp,
+                args.secret_key_file,
+                &oracle_config,
+                socket,
+                db_path,
+                allow_early_attestation,
+                seed,
+            )
+            .await;
+        }
+        Some(Command::RotateKey) => {
+            let keyring = rotate_keyring(&secp, args.secret_key_file).await?;
             info!(
-                "reading oracle config from {}",
-                path.as_os_str().to_string_lossy()
+                "oracle key rotated, new pubkey is {}",
+                keyring
+                    .active()
+                    .public_key()
+                    .serialize()
+                    .encode_hex::<String>()
             );
-            let mut oracle_config = String::new();
-            File::open(path)?.read_to_string(&mut oracle_config)?;
-            serde_json::from_str(&oracle_config)?
+            return Ok(());
         }
-    };
-    info!("oracle config successfully read: {:#?}", oracle_config);
-
-    // setup event databases
-    let oracles = asset_pair_infos
-        .iter()
-        .map(|asset_pair_info| asset_pair_info.asset_pair)
-        .zip(asset_pair_infos.iter().cloned().map(|asset_pair_info| {
-            let asset_pair = asset_pair_info.asset_pair;
+        Some(Command::Attest {
+            uuid,
+            asset_pair,
+            outcome,
+            early_attestation_reason,
+            offline: false,
+            url,
+        }) => {
+            return cli::attest_online(url, uuid, asset_pair, outcome, early_attestation_reason)
+                .await
+        }
+        // the other commands work on the event database, with the signer of the oracle
+        Some(command) => {
+            let signer =
+                load_signer(&secp, args.secret_key_file, args.signer_socket, false).await?;
+            let event_storage = EventHandler::new()?;
+            // read-only commands leave the event database as stored
+            let (_, oracles) = load_oracles(
+                &secp,
+                &signer,
+                &event_storage,
+                args.asset_pair_config_file,
+                args.oracle_config_file,
+                !command.is_read_only(),
+            )
+            .await?;
+            return match command {
+                Command::ExportEvents {
+                    asset_pair,
+                    include_secret_nonces,
+                    file,
+                } => cli::export_events(&oracles, asset_pair, include_secret_nonces, file).await,
+                Command::ImportEvents {
+                    file,
+                    overwrite,
+                    no_verify,
+                } => cli::import_events(&oracles, &secp, file, overwrite, no_verify).await,
+                Command::MigrateEvents {
+                    to,
+                    sled_path,
+                    storage_api_endpoint,
+                    database_url,
+                    overwrite,
+                } => {
+                    cli::migrate_events(
+                        &event_storage,
+                        to,
+                        sled_path,
+                        storage_api_endpoint,
+                        database_url,
+                        overwrite,
+                    )
+                    .await
+                }
+                Command::VerifyDb => cli::verify_db(&oracles, &secp).await,
+                Command::Attest {
+                    uuid,
+                    asset_pair,
+                    outcome,
+                    early_attestation_reason,
+                    ..
+                } => {
+                    cli::attest(
+                        &oracles,
+                        uuid,
+                        asset_pair,
+                        outcome,
+                        early_attestation_reason,
+                    )
+                    .await
+                }
+                Command::Keygen
+                | Command::ShowPubkey { .. }
+                | Command::RotateKey
+                | Command::Signer { .. } => {
+                    unreachable!("key commands do not load the event database")
+                }
+            };
+        }
+    }
+    let signer = load_signer(&secp, args.secret_key_file, args.signer_socket, true).await?;
+    info!(
+        "oracle signer ready, pubkey is {}",
+        signer.public_key().serialize().encode_hex::<String>()
+    );
+    if signer.keys().len() > 1 {
+        info!(
+            "{} retired keys kept to attest to the events they announced",
+            signer.keys().len() - 1
+        );
+    }
 
-            // create oracle
-            info!("creating oracle for {}", asset_pair);
-            let oracle = Oracle::new(oracle_config, keypair, secp.clone())?;
+    let api_keys = load_api_keys(args.api_key_file).await?;
+    let (oracle_config, oracles) = load_oracles(
+        &secp,
+        &signer,
+        &EventHandler::new()?,
+        args.asset_pair_config_file,
+        args.oracle_config_file,
+        true,
+    )
+    .await?;
 
-            Ok(oracle)
-        }))
-        .map(|(asset_pair, oracle)| oracle.map(|ok| (asset_pair, ok)))
-        .collect::<anyhow::Result<HashMap<_, _>>>()?;
+    // start announcement and attestation schedules
+    for oracle in oracles.values() {
+        oracle_scheduler::init(oracle.clone()).await?;
+    }
 
     // setup and run server
     let port: u16 = env::var("ORACLE_PORT")
@@ -612,17 +1448,48 @@ async fn main() -> anyhow::Result<()> {
         .parse()
         .unwrap_or(8080);
     info!("starting server on port {port}");
+    let suredbits_api = env::var("SUREDBITS_API_ENABLED")
+        .unwrap_or("false".to_string())
+        .parse::<bool>()
+        .unwrap_or(false)
+        .then(|| SuredbitsApi {
+            oracle_name: env::var("SUREDBITS_ORACLE_NAME").unwrap_or("sibyls".to_string()),
+        });
+    if suredbits_api.is_some() {
+        info!("serving the Suredbits oracle API under /v2");
+    }
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(oracles.clone()))
+            .app_data(web::Data::from(signer.clone()))
+            .app_data(web::Data::new(oracle_config))
+            .app_data(web::Data::new(api_keys.clone()))
             .service(
                 web::scope("/v1")
                     .service(announcements)
                     .service(get_announcement)
                     .service(publickey)
+                    .service(keys)
+                    .service(config)
+                    .service(oracle_info)
+                    .service(post_event)
+                    .service(post_attestation)
+                    .service(post_events)
+                    .service(post_attestations)
                     .service(attest)
+                    .service(verify)
                     .service(create_event),
             )
+            .configure(|cfg| {
+                if let Some(suredbits_api) = &suredbits_api {
+                    cfg.app_data(web::Data::new(suredbits_api.clone())).service(
+                        web::scope("/v2")
+                            .service(suredbits_announcements)
+                            .service(suredbits_announcement)
+                            .service(suredbits_oracle),
+                    );
+                }
+            })
     })
     .bind(("0.0.0.0", port))?
     // .bind(("54.198.187.245", 8080))? //TODO: Should we bind to only certain IPs for security?
@@ -631,3 +1498,74 @@ async fn main() -> anyhow::Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use actix_web::test;
+    use secp256k1_zkp::KeyPair;
+    use sibyls::oracle::Keyring;
+
+    fn oracles() -> HashMap<AssetPair, Oracle> {
+        let secp = Secp256k1::new();
+        let keypair = KeyPair::from_seckey_slice(&secp, &[1u8; 32]).unwrap();
+        let oracle_config = serde_json::from_value(serde_json::json!({
+            "attestation_time": "08:00",
+            "frequency": "1d",
+            "announcement_offset": "1d",
+        }))
+        .unwrap();
+        let asset_pair_info: AssetPairInfo = serde_json::from_value(serde_json::json!({
+            "asset_pair": "BTCUSD",
+            "event_descriptor": {
+                "base": 2,
+                "is_signed": false,
+                "unit": "BTCUSD",
+                "precision": 0,
+                "num_digits": 8,
+            },
+        }))
+        .unwrap();
+        let asset_pair = asset_pair_info.asset_pair.clone();
+        let oracle = Oracle::new(
+            oracle_config,
+            asset_pair_info,
+            EventHandler::memory().for_asset_pair(&asset_pair).unwrap(),
+            Arc::new(LocalSigner::new(
+                Keyring::new(keypair, vec![]),
+                secp.clone(),
+            )),
+            secp,
+        )
+        .unwrap();
+        HashMap::from([(asset_pair, oracle)])
+    }
+
+    #[actix_web::test]
+    async fn reports_unknown_events_of_batches_as_not_found() {
+        let app = test::init_service(
+            App::new()
+                .app_data(web::Data::new(oracles()))
+                .app_data(web::Data::new(ApiKeys::disabled()))
+                .service(post_attestations),
+        )
+        .await;
+        let request = test::TestRequest::post()
+            .uri("/batch/attestations")
+            .set_json(serde_json::json!({
+                "attestations": [
+                    { "uuid": "unknown", "outcome": 42 },
+                    { "uuid": "unknown", "asset_pair": "ETHUSD", "outcome": 42 },
+                ],
+            }))
+            .to_request();
+        let response: serde_json::Value = test::call_and_read_body_json(&app, request).await;
+        let statuses: Vec<_> = response["results"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|result| result["status"].as_u64().unwrap())
+            .collect();
+        assert_eq!(vec![404, 404], statuses);
+    }
+}
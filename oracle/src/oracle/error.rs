@@ -9,12 +9,107 @@ pub enum OracleError {
     /// nonpositive announcement time offset: {0}; announcement must happen before attestation
     InvalidAnnouncementTimeError(time::Duration),
 
+    /// nonpositive attestation frequency: {0}
+    InvalidFrequencyError(time::Duration),
+
     /// database error: {0}
     DatabaseError(#[from] sled::Error),
 
     /// storage api error: {0}
     StorageApiError(#[from] ApiError),
 
+    #[cfg(feature = "postgres")]
+    /// postgres error: {0}
+    PostgresError(#[from] diesel::result::Error),
+
+    #[cfg(feature = "postgres")]
+    /// postgres connection error: {0}
+    PostgresConnectionError(#[from] diesel::r2d2::PoolError),
+
+    /// postgres migration error: {0}
+    PostgresMigrationError(String),
+
+    /// invalid {0}: {1}
+    InvalidConfigError(&'static str, String),
+
+    /// stored record of event {0} is not valid base64
+    InvalidRecordError(String),
+
+    /// database or signer task failed: {0}
+    DatabaseTaskError(#[from] tokio::task::JoinError),
+
+    /// io error: {0}
+    IoError(#[from] std::io::Error),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+
+    /// secp256k1 upstream error: {0}
+    Secp256k1UpstreamError(#[from] secp256k1_zkp::UpstreamError),
+
+    /// pricefeed error: {0}
+    PriceFeedError(#[from] crate::oracle::pricefeeds::PriceFeedError),
+
+    /// announcement decoding error: {0}
+    AnnouncementDecodeError(String),
+
+    /// attestation decoding error: {0}
+    AttestationDecodeError(String),
+
+    /// invalid event descriptor: {0}
+    InvalidEventDescriptorError(String),
+
+    /// outcome {0} is not a possible outcome of the event
+    InvalidOutcomeError(String),
+
+    /// pricefeeds reported price {0}, which cannot be attested
+    InvalidPriceError(f64),
+
+    /// outcome {0} does not fit in the announced digits
+    OutcomeOutOfRangeError(i64),
+
+    /// event {0} is already attested with a different outcome
+    AlreadyAttestedError(String),
+
+    /// event {0} was already created by a request with a different idempotency key
+    IdempotencyKeyConflictError(String),
+
+    /// event was announced with key {0}, which is not a key of the oracle signer
+    UnknownOracleKeyError(String),
+
+    /// nonces of event {0} do not match its announcement
+    NonceMismatchError(String),
+
+    /// event {0} is of asset pair {1}, not of this oracle
+    AssetPairMismatchError(String, crate::AssetPair),
+
+    /// invalid oracle message: {0}
+    VerificationError(#[from] crate::VerificationError),
+
+    /// event {0} does not match its oracle messages: {1}
+    InconsistentEventError(String, String),
+
+    /// event {0} is already stored with another record
+    EventConflictError(String),
+
+    /// event {0} was not copied exactly
+    CopyMismatchError(String),
+
+    /// invalid event export: {0}
+    InvalidExportError(String),
+
+    /// unsupported stored event version: {0}
+    UnsupportedEventVersionError(String),
+
+    /// event {0} cannot be attested yet, it matures in {1}
+    PrematureAttestationError(String, time::Duration),
+
+    /// signer error: {0}
+    SignerError(#[from] crate::oracle::signer::SignerError),
+
+    /// got an unexpected event descriptor type
+    UnexpectedEventDescriptorError,
+
     /// event not found in redis
     EventNotFoundError,
 }
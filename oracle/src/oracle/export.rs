@@ -0,0 +1,326 @@
+use crate::{
+    oracle::{OracleError, Result, StoredEvent, STORED_EVENT_VERSION},
+    AssetPair, OracleEventDescriptor,
+};
+use hex::{FromHex, ToHex};
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, Write};
+use time::OffsetDateTime;
+
+/// Version of the event exports written by this oracle.
+pub const EXPORT_FORMAT_VERSION: u64 = 1;
+
+/// A line of an event export: exports are made of a header, followed by one line per event.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ExportRecord {
+    Header(ExportHeader),
+    Event(ExportedEvent),
+}
+
+/// Header of an event export.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportHeader {
+    /// version of the export, see [`EXPORT_FORMAT_VERSION`]
+    pub format_version: u64,
+    #[serde(with = "time::serde::rfc3339")]
+    pub exported_at: OffsetDateTime,
+    /// number of events following the header, to detect truncated exports
+    pub events: usize,
+}
+
+/// A [`StoredEvent`] as exported, with its oracle messages and secret nonces hex-encoded.
+///
+/// Secret nonces are only exported on request, and never those of attested events: together with
+/// the attestation, they reveal the oracle key.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ExportedEvent {
+    pub uuid: String,
+    pub asset_pair: AssetPair,
+    pub event_descriptor: OracleEventDescriptor,
+    #[serde(with = "time::serde::rfc3339")]
+    pub maturation: OffsetDateTime,
+    pub announcing_key: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    pub outstanding_sk_nonces: Option<Vec<String>>,
+    pub announcement: String,
+    pub suredbits_announcement: String,
+    pub attestation: Option<String>,
+    pub suredbits_attestation: Option<String>,
+    pub outcome: Option<i64>,
+    pub unclamped_outcome: Option<i64>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub attested_at: Option<OffsetDateTime>,
+    pub early_attestation_reason: Option<String>,
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    pub idempotency_key: Option<String>,
+    pub attestation_idempotency_key: Option<String>,
+}
+
+impl ExportedEvent {
+    /// Returns the export of `event`, with its secret nonces if it is not attested and
+    /// `include_secret_nonces` is set.
+    pub fn new(event: &StoredEvent, include_secret_nonces: bool) -> Self {
+        ExportedEvent {
+            uuid: event.uuid.clone(),
+            asset_pair: event.asset_pair.clone(),
+            event_descriptor: event.event_descriptor.clone(),
+            maturation: event.maturation,
+            announcing_key: event.announcing_key.clone(),
+            created_at: event.created_at,
+            outstanding_sk_nonces: event
+                .outstanding_sk_nonces
+                .as_ref()
+                .filter(|_| include_secret_nonces && event.attestation.is_none())
+                .map(|sk_nonces| sk_nonces.iter().map(|nonce| nonce.encode_hex()).collect()),
+            announcement: event.announcement.encode_hex(),
+            suredbits_announcement: event.suredbits_announcement.encode_hex(),
+            attestation: event.attestation.as_ref().map(|a| a.encode_hex()),
+            suredbits_attestation: event.suredbits_attestation.as_ref().map(|a| a.encode_hex()),
+            outcome: event.outcome,
+            unclamped_outcome: event.unclamped_outcome,
+            attested_at: event.attested_at,
+            early_attestation_reason: event.early_attestation_reason.clone(),
+            metadata: event.metadata.clone(),
+            idempotency_key: event.idempotency_key.clone(),
+            attestation_idempotency_key: event.attestation_idempotency_key.clone(),
+        }
+    }
+}
+
+impl TryFrom<ExportedEvent> for StoredEvent {
+    type Error = OracleError;
+
+    fn try_from(event: ExportedEvent) -> Result<Self> {
+        let invalid = |e: hex::FromHexError| {
+            OracleError::InvalidExportError(format!("event {}: {}", event.uuid, e))
+        };
+        Ok(StoredEvent {
+            version: STORED_EVENT_VERSION,
+            // the nonces of attested events are never kept, even if exported by mistake
+            outstanding_sk_nonces: event
+                .outstanding_sk_nonces
+                .as_ref()
+                .filter(|_| event.attestation.is_none())
+                .map(|sk_nonces| {
+                    sk_nonces
+                        .iter()
+                        .map(<[u8; 32]>::from_hex)
+                        .collect::<std::result::Result<Vec<_>, _>>()
+                })
+                .transpose()
+                .map_err(invalid)?,
+            announcement: Vec::from_hex(&event.announcement).map_err(invalid)?,
+            suredbits_announcement: Vec::from_hex(&event.suredbits_announcement)
+                .map_err(invalid)?,
+            attestation: event
+                .attestation
+                .as_ref()
+                .map(Vec::from_hex)
+                .transpose()
+                .map_err(invalid)?,
+            suredbits_attestation: event
+                .suredbits_attestation
+                .as_ref()
+                .map(Vec::from_hex)
+                .transpose()
+                .map_err(invalid)?,
+            uuid: event.uuid,
+            asset_pair: event.asset_pair,
+            event_descriptor: event.event_descriptor,
+            maturation: event.maturation,
+            announcing_key: event.announcing_key,
+            created_at: event.created_at,
+            outcome: event.outcome,
+            unclamped_outcome: event.unclamped_outcome,
+            attested_at: event.attested_at,
+            early_attestation_reason: event.early_attestation_reason,
+            metadata: event.metadata,
+            idempotency_key: event.idempotency_key,
+            attestation_idempotency_key: event.attestation_idempotency_key,
+        })
+    }
+}
+
+/// Writes an export of `events` to `writer`, as newline-delimited JSON [`ExportRecord`]s, with the
+/// secret nonces of unattested events if `include_secret_nonces` is set, see [`ExportedEvent`].
+pub fn write_export<W: Write>(
+    writer: &mut W,
+    events: &[StoredEvent],
+    include_secret_nonces: bool,
+) -> Result<()> {
+    let header = ExportRecord::Header(ExportHeader {
+        format_version: EXPORT_FORMAT_VERSION,
+        exported_at: OffsetDateTime::now_utc(),
+        events: events.len(),
+    });
+    serde_json::to_writer(&mut *writer, &header)?;
+    writer.write_all(b"\n")?;
+    for event in events {
+        serde_json::to_writer(
+            &mut *writer,
+            &ExportRecord::Event(ExportedEvent::new(event, include_secret_nonces)),
+        )?;
+        writer.write_all(b"\n")?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// Reads the events of an export written by [`write_export`], failing if it is of an unsupported
+/// version or truncated.
+pub fn read_export<R: BufRead>(reader: R) -> Result<Vec<StoredEvent>> {
+    let mut lines = reader.lines().enumerate();
+    let header = match lines.next() {
+        Some((_, line)) => match serde_json::from_str(&line?)? {
+            ExportRecord::Header(header) => header,
+            ExportRecord::Event(_) => {
+                return Err(OracleError::InvalidExportError(
+                    "export does not start with a header".to_string(),
+                ))
+            }
+        },
+        None => {
+            return Err(OracleError::InvalidExportError(
+                "export is empty".to_string(),
+            ))
+        }
+    };
+    if header.format_version != EXPORT_FORMAT_VERSION {
+        return Err(OracleError::InvalidExportError(format!(
+            "unsupported export version {}",
+            header.format_version
+        )));
+    }
+
+    let mut events = vec![];
+    for (index, line) in lines {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        match serde_json::from_str(&line)? {
+            ExportRecord::Event(event) => events.push(event.try_into()?),
+            ExportRecord::Header(_) => {
+                return Err(OracleError::InvalidExportError(format!(
+                    "unexpected header on line {}",
+                    index + 1
+                )))
+            }
+        }
+    }
+    if events.len() != header.events {
+        return Err(OracleError::InvalidExportError(format!(
+            "export announces {} events but has {}",
+            header.events,
+            events.len()
+        )));
+    }
+    Ok(events)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        oracle::{oracle_scheduler::build_announcement, test_utils::local_signer},
+        EventDescriptor,
+    };
+
+    fn stored_event() -> StoredEvent {
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let event_descriptor: OracleEventDescriptor = EventDescriptor {
+            base: 2,
+            is_signed: false,
+            unit: "BTCUSD".to_string(),
+            precision: 0,
+            num_digits: 4,
+        }
+        .into();
+        let announcement = build_announcement(
+            &local_signer(),
+            &"BTCUSD".parse().unwrap(),
+            maturation,
+            "event".to_string(),
+            event_descriptor.clone(),
+        )
+        .unwrap();
+        StoredEvent {
+            version: STORED_EVENT_VERSION,
+            uuid: "event".to_string(),
+            asset_pair: "BTCUSD".parse().unwrap(),
+            event_descriptor,
+            maturation,
+            announcing_key: keypair.public_key().to_string(),
+            created_at: Some(maturation - time::Duration::HOUR),
+            outstanding_sk_nonces: Some(vec![[7u8; 32]; 4]),
+            announcement: announcement.encode(),
+            suredbits_announcement: announcement.suredbits_encode(),
+            attestation: None,
+            suredbits_attestation: None,
+            outcome: None,
+            unclamped_outcome: None,
+            attested_at: None,
+            early_attestation_reason: None,
+            metadata: Some(
+                serde_json::json!({"source": "test"})
+                    .as_object()
+                    .unwrap()
+                    .clone(),
+            ),
+            idempotency_key: Some("key".to_string()),
+            attestation_idempotency_key: None,
+        }
+    }
+
+    #[test]
+    fn exports_events_exactly() {
+        let events = vec![stored_event()];
+        let mut export = vec![];
+        write_export(&mut export, &events, true).unwrap();
+
+        let read = read_export(&export[..]).unwrap();
+        assert_eq!(1, read.len());
+        assert_eq!(events[0].to_record().unwrap(), read[0].to_record().unwrap());
+    }
+
+    #[test]
+    fn exports_secret_nonces_of_unattested_events_on_request_only() {
+        let unattested = stored_event();
+        let mut attested = stored_event();
+        attested.attestation = Some(vec![1]);
+
+        assert!(ExportedEvent::new(&unattested, true)
+            .outstanding_sk_nonces
+            .is_some());
+        assert!(ExportedEvent::new(&unattested, false)
+            .outstanding_sk_nonces
+            .is_none());
+        assert!(ExportedEvent::new(&attested, true)
+            .outstanding_sk_nonces
+            .is_none());
+
+        let mut exported = ExportedEvent::new(&unattested, true);
+        exported.attestation = Some("01".to_string());
+        let imported: StoredEvent = exported.try_into().unwrap();
+        assert!(imported.outstanding_sk_nonces.is_none());
+    }
+
+    #[test]
+    fn rejects_truncated_exports() {
+        let mut export = vec![];
+        write_export(&mut export, &[stored_event(), stored_event()], false).unwrap();
+        let truncated = export
+            .split_inclusive(|byte| *byte == b'\n')
+            .take(2)
+            .flatten()
+            .copied()
+            .collect::<Vec<_>>();
+
+        assert!(matches!(
+            read_export(&truncated[..]),
+            Err(OracleError::InvalidExportError(_))
+        ));
+    }
+}
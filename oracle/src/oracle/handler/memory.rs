@@ -0,0 +1,110 @@
+use super::EventStore;
+use crate::{oracle::OracleError, AssetPair};
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::{
+    collections::{BTreeMap, HashMap},
+    ops::Bound::{Excluded, Unbounded},
+    sync::Arc,
+};
+
+/// Event store holding events in memory, e.g. to test the oracle without disk or network.
+/// Clones share their events.
+#[derive(Clone, Default)]
+pub struct MemoryStore {
+    // records by event id, of each namespace by asset pair, `None` outside of any namespace
+    namespaces: Arc<Mutex<HashMap<Option<AssetPair>, BTreeMap<String, Vec<u8>>>>>,
+    namespace: Option<AssetPair>,
+}
+
+#[async_trait]
+impl EventStore for MemoryStore {
+    fn namespace(&self, asset_pair: &AssetPair) -> Result<Box<dyn EventStore>, OracleError> {
+        Ok(Box::new(Self {
+            namespaces: self.namespaces.clone(),
+            namespace: Some(asset_pair.clone()),
+        }))
+    }
+
+    async fn namespaces(&self) -> Result<Vec<AssetPair>, OracleError> {
+        Ok(self.namespaces.lock().keys().flatten().cloned().collect())
+    }
+
+    async fn insert(&self, event_id: String, record: Vec<u8>) -> Result<(), OracleError> {
+        self.namespaces
+            .lock()
+            .entry(self.namespace.clone())
+            .or_default()
+            .insert(event_id, record);
+        Ok(())
+    }
+
+    async fn insert_all(&self, records: Vec<(String, Vec<u8>)>) -> Result<(), OracleError> {
+        self.namespaces
+            .lock()
+            .entry(self.namespace.clone())
+            .or_default()
+            .extend(records);
+        Ok(())
+    }
+
+    async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError> {
+        Ok(self
+            .namespaces
+            .lock()
+            .get(&self.namespace)
+            .and_then(|records| records.get(&event_id).cloned()))
+    }
+
+    async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        Ok(self
+            .namespaces
+            .lock()
+            .get(&self.namespace)
+            .map(|records| {
+                records
+                    .iter()
+                    .map(|(event_id, record)| (event_id.clone(), record.clone()))
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let namespaces = self.namespaces.lock();
+        let records = match namespaces.get(&self.namespace) {
+            Some(records) => records,
+            None => return Ok(vec![]),
+        };
+        let range: Box<dyn Iterator<Item = (&String, &Vec<u8>)>> = match (cursor, reverse) {
+            (Some(cursor), false) => Box::new(records.range((Excluded(cursor), Unbounded))),
+            (Some(cursor), true) => Box::new(records.range((Unbounded, Excluded(cursor))).rev()),
+            (None, false) => Box::new(records.iter()),
+            (None, true) => Box::new(records.iter().rev()),
+        };
+        Ok(range
+            .take(limit)
+            .map(|(event_id, record)| (event_id.clone(), record.clone()))
+            .collect())
+    }
+
+    async fn count(&self) -> Result<usize, OracleError> {
+        Ok(self
+            .namespaces
+            .lock()
+            .get(&self.namespace)
+            .map_or(0, |records| records.len()))
+    }
+
+    async fn remove(&self, event_id: String) -> Result<(), OracleError> {
+        if let Some(records) = self.namespaces.lock().get_mut(&self.namespace) {
+            records.remove(&event_id);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,516 @@
+use crate::{oracle::OracleError, AssetPair};
+use async_trait::async_trait;
+use log::info;
+use parking_lot::Mutex;
+use std::{collections::HashMap, env, sync::Arc};
+
+mod memory;
+#[cfg(feature = "postgres")]
+mod postgres;
+mod sled;
+mod storage_api;
+pub use self::sled::SledStore;
+pub use memory::MemoryStore;
+#[cfg(feature = "postgres")]
+pub use postgres::PostgresStore;
+pub use storage_api::StorageApiConn;
+
+// separates the asset pair from the event id in storage api keys
+const NAMESPACE_SEPARATOR: char = ':';
+// events written at once when copying events, to bound the size of storage api requests
+const COPY_BATCH_SIZE: usize = 1000;
+
+/// Computes the records to write from the stored records, see [`EventStore::update`]. It may block,
+/// e.g. on a remote signer, so stores call it off the async runtime.
+pub type RecordsUpdate =
+    Box<dyn FnOnce(Vec<Option<Vec<u8>>>) -> Result<Vec<Option<Vec<u8>>>, OracleError> + Send>;
+
+/// A database of event records, keyed by event id.
+///
+/// Events of each asset pair are stored in their own namespace of the database, see
+/// [`EventStore::namespace`]. The events stored outside of any namespace are those stored before
+/// asset pairs had their own.
+#[async_trait]
+pub trait EventStore: Send + Sync {
+    /// Returns the store of the namespace of `asset_pair` in this database.
+    fn namespace(&self, asset_pair: &AssetPair) -> Result<Box<dyn EventStore>, OracleError>;
+
+    /// Returns the asset pairs which have a namespace in this database.
+    async fn namespaces(&self) -> Result<Vec<AssetPair>, OracleError>;
+
+    /// Inserts or replaces the record of `event_id`.
+    async fn insert(&self, event_id: String, record: Vec<u8>) -> Result<(), OracleError>;
+
+    /// Inserts or replaces all `records` at once: either all are inserted or none is.
+    async fn insert_all(&self, records: Vec<(String, Vec<u8>)>) -> Result<(), OracleError>;
+
+    /// Returns the record of `event_id`, if any.
+    async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError>;
+
+    /// Returns the records of all events, with their event id.
+    async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError>;
+
+    /// Returns the records of at most `limit` events, with their event id, in order of event id:
+    /// the first ones after `cursor`, or the last ones before it in reverse order if `reverse` is
+    /// set. Pages may be shorter than `limit` before the last one, which is empty.
+    ///
+    /// By default all records are read with [`EventStore::get_all`], stores should only read the
+    /// page instead.
+    async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let mut records = self.get_all().await?;
+        records.sort_by(|(a, _), (b, _)| a.cmp(b));
+        if reverse {
+            records.reverse();
+        }
+        Ok(records
+            .into_iter()
+            .filter(|(event_id, _)| match &cursor {
+                Some(cursor) if reverse => event_id < cursor,
+                Some(cursor) => event_id > cursor,
+                None => true,
+            })
+            .take(limit)
+            .collect())
+    }
+
+    /// Returns the number of events.
+    ///
+    /// By default all records are read with [`EventStore::get_all`], stores should only count
+    /// them instead.
+    async fn count(&self) -> Result<usize, OracleError> {
+        Ok(self.get_all().await?.len())
+    }
+
+    /// Removes the record of `event_id`, if any.
+    async fn remove(&self, event_id: String) -> Result<(), OracleError>;
+
+    /// Updates the records of the distinct `event_ids` at once: `update` is called once with their
+    /// stored records, in order, and returns the records to write in their place, `None` leaving a
+    /// record unchanged. Nothing is written if `update` fails.
+    ///
+    /// By default the records are read then written with [`EventStore::insert_all`], so writers
+    /// must not update the same records concurrently. Stores shared by several oracle processes
+    /// should lock the records until the update is written instead.
+    async fn update(
+        &self,
+        event_ids: Vec<String>,
+        update: RecordsUpdate,
+    ) -> Result<(), OracleError> {
+        let mut records = Vec::with_capacity(event_ids.len());
+        for event_id in &event_ids {
+            records.push(self.get(event_id.clone()).await?);
+        }
+        let updated = tokio::task::spawn_blocking(move || update(records)).await??;
+        let writes: Vec<_> = event_ids
+            .into_iter()
+            .zip(updated)
+            .filter_map(|(event_id, record)| Some((event_id, record?)))
+            .collect();
+        if writes.is_empty() {
+            return Ok(());
+        }
+        self.insert_all(writes).await
+    }
+}
+
+/// Returns the url of the postgres database to store events in directly, from
+/// `ORACLE_DATABASE_URL`, if any.
+pub fn postgres_database_url() -> Option<String> {
+    env::var("ORACLE_DATABASE_URL")
+        .ok()
+        .filter(|url| !url.is_empty())
+}
+
+/// Returns the size of the postgres connection pool, from `ORACLE_DATABASE_POOL_SIZE`.
+#[cfg(feature = "postgres")]
+pub fn postgres_pool_size() -> Result<u32, OracleError> {
+    parse_env_var("ORACLE_DATABASE_POOL_SIZE", 10)
+}
+
+/// Returns whether the storage api migrations are applied to the postgres database on start, from
+/// `ORACLE_DATABASE_MIGRATE`.
+#[cfg(feature = "postgres")]
+pub fn postgres_migrate() -> Result<bool, OracleError> {
+    parse_env_var("ORACLE_DATABASE_MIGRATE", false)
+}
+
+// returns `default` if `name` is unset or empty, and an error naming it if it does not parse
+#[cfg(feature = "postgres")]
+fn parse_env_var<T>(name: &'static str, default: T) -> Result<T, OracleError>
+where
+    T: std::str::FromStr,
+    T::Err: std::fmt::Display,
+{
+    match env::var(name) {
+        Ok(value) if !value.is_empty() => value
+            .parse()
+            .map_err(|e| OracleError::InvalidConfigError(name, format!("{}: {}", value, e))),
+        _ => Ok(default),
+    }
+}
+
+/// Returns whether events are stored through the storage api, from `STORAGE_API_ENABLED`, or in
+/// sled.
+pub fn storage_api_enabled() -> bool {
+    env::var("STORAGE_API_ENABLED")
+        .unwrap_or("false".to_string())
+        .parse()
+        .unwrap()
+}
+
+/// Returns the path of the sled event database, from `ORACLE_EVENTS_DB_PATH`.
+pub fn sled_path() -> String {
+    let oracle_events_db_path: String = env::var("ORACLE_EVENTS_DB_PATH").unwrap_or("".to_string());
+    match oracle_events_db_path.is_empty() {
+        true => "events_db".to_string(),
+        false => oracle_events_db_path,
+    }
+}
+
+/// Returns the endpoint of the storage api, from `STORAGE_API_ENDPOINT`.
+pub fn storage_api_endpoint() -> String {
+    env::var("STORAGE_API_ENDPOINT").unwrap_or("http://localhost:8100".to_string())
+}
+
+/// Handler of the event database, whichever [`EventStore`] backs it.
+#[derive(Clone)]
+pub struct EventHandler {
+    store: Arc<dyn EventStore>,
+}
+
+impl EventHandler {
+    /// Returns a handler of the event database configured by the environment: postgres when
+    /// `ORACLE_DATABASE_URL` is set, the storage api when `STORAGE_API_ENABLED` is set, else sled.
+    pub fn new() -> Result<Self, OracleError> {
+        #[cfg(feature = "postgres")]
+        if let Some(database_url) = postgres_database_url() {
+            return Self::postgres(&database_url, postgres_pool_size()?, postgres_migrate()?);
+        }
+        #[cfg(not(feature = "postgres"))]
+        if postgres_database_url().is_some() {
+            return Err(OracleError::InvalidConfigError(
+                "ORACLE_DATABASE_URL",
+                "sibyls was built without the postgres feature".to_string(),
+            ));
+        }
+        let use_storage_api = storage_api_enabled();
+        info!("Storage api enabled: {}", use_storage_api);
+        if use_storage_api {
+            Ok(Self::storage_api(storage_api_endpoint()))
+        } else {
+            Self::sled(&sled_path())
+        }
+    }
+
+    /// Returns a handler of the events stored in the sled database at `path`.
+    pub fn sled(path: &str) -> Result<Self, OracleError> {
+        Ok(Self::with_store(SledStore::open(path)?))
+    }
+
+    /// Returns a handler of the events stored through the storage api at `endpoint`.
+    pub fn storage_api(endpoint: String) -> Self {
+        Self::with_store(StorageApiConn::new(endpoint))
+    }
+
+    /// Returns a handler of the events stored directly in the postgres database at
+    /// `database_url`, through a pool of at most `pool_size` connections. The migrations of the
+    /// storage api are applied first if `migrate` is set.
+    #[cfg(feature = "postgres")]
+    pub fn postgres(
+        database_url: &str,
+        pool_size: u32,
+        migrate: bool,
+    ) -> Result<Self, OracleError> {
+        Ok(Self::with_store(PostgresStore::connect(
+            database_url,
+            pool_size,
+            migrate,
+        )?))
+    }
+
+    /// Returns a handler of events stored in memory, which are lost once the handler and its
+    /// clones are dropped.
+    pub fn memory() -> Self {
+        Self::with_store(MemoryStore::default())
+    }
+
+    /// Returns a handler of the events stored in `store`.
+    pub fn with_store(store: impl EventStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+        }
+    }
+
+    /// Returns a handler of the events of `asset_pair` only, which are stored in their own
+    /// namespace, see [`EventStore::namespace`].
+    pub fn for_asset_pair(&self, asset_pair: &AssetPair) -> Result<Self, OracleError> {
+        Ok(Self {
+            store: Arc::from(self.store.namespace(asset_pair)?),
+        })
+    }
+
+    /// Returns the asset pairs which have a namespace in the event database.
+    pub async fn asset_pairs(&self) -> Result<Vec<AssetPair>, OracleError> {
+        let mut asset_pairs = self.store.namespaces().await?;
+        asset_pairs.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        asset_pairs.dedup();
+        Ok(asset_pairs)
+    }
+
+    /// Moves the events stored before asset pairs had their own namespace into the namespace of
+    /// `asset_pair`, returning how many were moved.
+    pub async fn migrate_unnamespaced_events(
+        &self,
+        asset_pair: &AssetPair,
+    ) -> Result<usize, OracleError> {
+        let namespace = self.store.namespace(asset_pair)?;
+        let mut migrated = 0;
+        for (event_id, record) in self.store.get_all().await? {
+            namespace.insert(event_id.clone(), record).await?;
+            self.store.remove(event_id).await?;
+            migrated += 1;
+        }
+        Ok(migrated)
+    }
+
+    /// Copies the events of every asset pair to `destination`, returning how many were copied.
+    ///
+    /// Records are copied byte for byte, and read back from `destination` to check they were.
+    /// Events which `destination` already stores with another record are only replaced if
+    /// `overwrite` is set, the copy failing before any write otherwise.
+    pub async fn copy_events(
+        &self,
+        destination: &EventHandler,
+        overwrite: bool,
+    ) -> Result<usize, OracleError> {
+        let mut copied = 0;
+        for asset_pair in self.asset_pairs().await? {
+            let source = self.for_asset_pair(&asset_pair)?;
+            let target = destination.for_asset_pair(&asset_pair)?;
+            let records = source.get_all().await?;
+            if !overwrite {
+                let existing: HashMap<_, _> = target.get_all().await?.into_iter().collect();
+                for (event_id, record) in &records {
+                    if existing
+                        .get(event_id)
+                        .map_or(false, |stored| stored != record)
+                    {
+                        return Err(OracleError::EventConflictError(format!(
+                            "{}{}{}",
+                            asset_pair, NAMESPACE_SEPARATOR, event_id
+                        )));
+                    }
+                }
+            }
+            for batch in records.chunks(COPY_BATCH_SIZE) {
+                target.insert_all(batch.to_vec()).await?;
+            }
+
+            let copies: HashMap<_, _> = target.get_all().await?.into_iter().collect();
+            for (event_id, record) in &records {
+                if copies.get(event_id) != Some(record) {
+                    return Err(OracleError::CopyMismatchError(format!(
+                        "{}{}{}",
+                        asset_pair, NAMESPACE_SEPARATOR, event_id
+                    )));
+                }
+            }
+            info!("copied {} events of {}", records.len(), asset_pair);
+            copied += records.len();
+        }
+        Ok(copied)
+    }
+
+    pub async fn insert(&self, event_id: String, new_event: Vec<u8>) -> Result<(), OracleError> {
+        self.store.insert(event_id, new_event).await
+    }
+
+    /// Inserts all `events` at once: either all are inserted or none is.
+    pub async fn insert_all(&self, events: Vec<(String, Vec<u8>)>) -> Result<(), OracleError> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.store.insert_all(events).await
+    }
+
+    /// Updates the events of the distinct `event_ids` at once, see [`EventStore::update`]:
+    /// `update` returns the records to write along with its output, which is returned once the
+    /// records are written.
+    pub async fn update<T, F>(&self, event_ids: Vec<String>, update: F) -> Result<T, OracleError>
+    where
+        T: Send + 'static,
+        F: FnOnce(Vec<Option<Vec<u8>>>) -> Result<(Vec<Option<Vec<u8>>>, T), OracleError>
+            + Send
+            + 'static,
+    {
+        let output = Arc::new(Mutex::new(None));
+        let update_output = output.clone();
+        self.store
+            .update(
+                event_ids,
+                Box::new(move |records| {
+                    let (records, value) = update(records)?;
+                    *update_output.lock() = Some(value);
+                    Ok(records)
+                }),
+            )
+            .await?;
+        let value = output.lock().take();
+        Ok(value.expect("event stores call update once"))
+    }
+
+    pub async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError> {
+        self.store.get(event_id).await
+    }
+
+    pub async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        self.store.get_all().await
+    }
+
+    /// Returns the number of events, see [`EventStore::count`].
+    pub async fn count(&self) -> Result<usize, OracleError> {
+        self.store.count().await
+    }
+
+    /// Returns a page of at most `limit` events, see [`EventStore::get_page`].
+    pub async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        self.store.get_page(cursor, limit, reverse).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn copies_events() {
+        let source = EventHandler::memory();
+        let destination = EventHandler::memory();
+        let btcusd = source.for_asset_pair(&"BTCUSD".parse().unwrap()).unwrap();
+        btcusd.insert("a".to_string(), vec![1]).await.unwrap();
+        let ethusd = source.for_asset_pair(&"ETHUSD".parse().unwrap()).unwrap();
+        ethusd.insert("b".to_string(), vec![2]).await.unwrap();
+
+        assert_eq!(2, source.copy_events(&destination, false).await.unwrap());
+        assert_eq!(
+            source.asset_pairs().await.unwrap(),
+            destination.asset_pairs().await.unwrap()
+        );
+        let copied = destination
+            .for_asset_pair(&"ETHUSD".parse().unwrap())
+            .unwrap();
+        assert_eq!(
+            vec![("b".to_string(), vec![2])],
+            copied.get_all().await.unwrap()
+        );
+
+        btcusd.insert("a".to_string(), vec![3]).await.unwrap();
+        assert!(matches!(
+            source.copy_events(&destination, false).await,
+            Err(OracleError::EventConflictError(_))
+        ));
+        assert_eq!(2, source.copy_events(&destination, true).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn updates_records() {
+        let handler = EventHandler::memory();
+        handler.insert("a".to_string(), vec![1]).await.unwrap();
+
+        let stored = handler
+            .update(vec!["a".to_string(), "b".to_string()], |records| {
+                Ok((vec![None, Some(vec![2])], records))
+            })
+            .await
+            .unwrap();
+        assert_eq!(vec![Some(vec![1]), None], stored);
+        assert_eq!(
+            vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])],
+            handler.get_all().await.unwrap()
+        );
+
+        assert!(handler
+            .update(vec!["a".to_string()], |_| -> Result<(_, ()), _> {
+                Err(OracleError::EventNotFoundError)
+            })
+            .await
+            .is_err());
+        assert_eq!(Some(vec![1]), handler.get("a".to_string()).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn migrates_unnamespaced_events() {
+        let handler = EventHandler::memory();
+        handler.insert("a".to_string(), vec![1]).await.unwrap();
+        let btcusd = "BTCUSD".parse().unwrap();
+
+        assert_eq!(
+            1,
+            handler.migrate_unnamespaced_events(&btcusd).await.unwrap()
+        );
+        assert!(handler.get_all().await.unwrap().is_empty());
+        assert_eq!(
+            Some(vec![1]),
+            handler
+                .for_asset_pair(&btcusd)
+                .unwrap()
+                .get("a".to_string())
+                .await
+                .unwrap()
+        );
+    }
+
+    #[tokio::test]
+    async fn pages_events() {
+        let db = ::sled::Config::new().temporary(true).open().unwrap();
+        let btcusd = "BTCUSD".parse().unwrap();
+        for handler in [
+            EventHandler::memory(),
+            EventHandler::with_store(SledStore::new(db)),
+        ] {
+            handler.insert("z".to_string(), vec![0]).await.unwrap();
+            let handler = handler.for_asset_pair(&btcusd).unwrap();
+            for (event_id, record) in [("a", 1), ("b", 2), ("c", 3)] {
+                handler
+                    .insert(event_id.to_string(), vec![record])
+                    .await
+                    .unwrap();
+            }
+
+            assert_eq!(3, handler.count().await.unwrap());
+            assert_eq!(
+                vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])],
+                handler.get_page(None, 2, false).await.unwrap()
+            );
+            assert_eq!(
+                vec![("c".to_string(), vec![3])],
+                handler
+                    .get_page(Some("b".to_string()), 2, false)
+                    .await
+                    .unwrap()
+            );
+            assert!(handler
+                .get_page(Some("c".to_string()), 2, false)
+                .await
+                .unwrap()
+                .is_empty());
+            assert_eq!(
+                vec![("b".to_string(), vec![2]), ("a".to_string(), vec![1])],
+                handler
+                    .get_page(Some("c".to_string()), 2, true)
+                    .await
+                    .unwrap()
+            );
+        }
+    }
+}
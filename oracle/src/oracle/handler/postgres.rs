@@ -0,0 +1,236 @@
+extern crate base64;
+use super::{EventStore, RecordsUpdate, NAMESPACE_SEPARATOR};
+use crate::{oracle::OracleError, AssetPair};
+use async_trait::async_trait;
+use diesel::{
+    r2d2::{ConnectionManager, Pool, PooledConnection},
+    result::Error as DieselError,
+    Connection, PgConnection,
+};
+use dlc_storage_common::models::{Event, NewEvent};
+use log::info;
+use std::collections::HashMap;
+
+type PgPool = Pool<ConnectionManager<PgConnection>>;
+
+/// Event store writing directly to the `events` table of the storage api database, with the same
+/// keys and encoding as [`super::StorageApiConn`], so that both can share a database.
+///
+/// Updates lock the events they read until they are written, so that oracles sharing the database
+/// cannot attest the same event twice.
+#[derive(Clone)]
+pub struct PostgresStore {
+    pool: PgPool,
+
+    // prepended to event ids to form the database keys, empty outside of any namespace
+    prefix: String,
+}
+
+impl PostgresStore {
+    /// Connects to the postgres database at `database_url` through a pool of at most `pool_size`
+    /// connections, applying the migrations of the storage api first if `migrate` is set.
+    pub fn connect(database_url: &str, pool_size: u32, migrate: bool) -> Result<Self, OracleError> {
+        info!("connecting to postgres event database");
+        let pool = Pool::builder()
+            .max_size(pool_size)
+            .build(ConnectionManager::<PgConnection>::new(database_url))?;
+        if migrate {
+            info!("applying migrations to postgres event database");
+            dlc_storage_common::run_migrations(&mut pool.get()?)
+                .map_err(|e| OracleError::PostgresMigrationError(e.to_string()))?;
+        }
+        Ok(Self {
+            pool,
+            prefix: String::new(),
+        })
+    }
+
+    fn key(&self, event_id: &str) -> String {
+        format!("{}{}", self.prefix, event_id)
+    }
+
+    // returns the event id of database key `key` in this namespace, if it is in it
+    fn event_id<'a>(&self, key: &'a str) -> Option<&'a str> {
+        if self.prefix.is_empty() && key.contains(NAMESPACE_SEPARATOR) {
+            return None;
+        }
+        key.strip_prefix(&self.prefix)
+    }
+
+    // runs `query` with a pooled connection, off the async runtime as diesel blocks
+    async fn run<T, F>(&self, query: F) -> Result<T, OracleError>
+    where
+        T: Send + 'static,
+        F: FnOnce(&mut PooledConnection<ConnectionManager<PgConnection>>) -> Result<T, OracleError>
+            + Send
+            + 'static,
+    {
+        let pool = self.pool.clone();
+        tokio::task::spawn_blocking(move || query(&mut pool.get()?)).await?
+    }
+}
+
+fn decode(event: Event) -> Result<(String, Vec<u8>), OracleError> {
+    match base64::decode(&event.content) {
+        Ok(record) => Ok((event.event_id, record)),
+        Err(_) => Err(OracleError::InvalidRecordError(event.event_id)),
+    }
+}
+
+#[async_trait]
+impl EventStore for PostgresStore {
+    fn namespace(&self, asset_pair: &AssetPair) -> Result<Box<dyn EventStore>, OracleError> {
+        Ok(Box::new(Self {
+            pool: self.pool.clone(),
+            prefix: format!("{}{}", asset_pair, NAMESPACE_SEPARATOR),
+        }))
+    }
+
+    async fn namespaces(&self) -> Result<Vec<AssetPair>, OracleError> {
+        let events = self
+            .run(|conn| Ok(dlc_storage_common::get_all_events(conn)?))
+            .await?;
+        Ok(events
+            .into_iter()
+            .filter_map(|event| {
+                let (asset_pair, _) = event.event_id.split_once(NAMESPACE_SEPARATOR)?;
+                asset_pair.parse().ok()
+            })
+            .collect())
+    }
+
+    async fn insert(&self, event_id: String, record: Vec<u8>) -> Result<(), OracleError> {
+        self.insert_all(vec![(event_id, record)]).await
+    }
+
+    /// Upserts all `records` with a single statement.
+    async fn insert_all(&self, records: Vec<(String, Vec<u8>)>) -> Result<(), OracleError> {
+        let events: Vec<NewEvent> = records
+            .into_iter()
+            .map(|(event_id, record)| NewEvent {
+                event_id: self.key(&event_id),
+                content: base64::encode(record),
+            })
+            .collect();
+        self.run(move |conn| {
+            dlc_storage_common::upsert_events(conn, events)?;
+            Ok(())
+        })
+        .await
+    }
+
+    async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError> {
+        let key = self.key(&event_id);
+        let event = self
+            .run(
+                move |conn| match dlc_storage_common::get_event(conn, &key) {
+                    Ok(event) => Ok(Some(event)),
+                    Err(DieselError::NotFound) => Ok(None),
+                    Err(e) => Err(e.into()),
+                },
+            )
+            .await?;
+        Ok(event.map(decode).transpose()?.map(|(_, record)| record))
+    }
+
+    async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let prefix = self.prefix.clone();
+        let events = self
+            .run(move |conn| Ok(dlc_storage_common::get_events_with_prefix(conn, &prefix)?))
+            .await?;
+        let mut result = vec![];
+        for event in events {
+            let (key, record) = decode(event)?;
+            if let Some(event_id) = self.event_id(&key) {
+                result.push((event_id.to_string(), record));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Reads the page only, with a single query.
+    async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let prefix = self.prefix.clone();
+        let cursor = cursor.map(|cursor| self.key(&cursor));
+        let limit = i64::try_from(limit).unwrap_or(i64::MAX);
+        let events = self
+            .run(move |conn| {
+                Ok(dlc_storage_common::get_events_page(
+                    conn, &prefix, cursor, limit, reverse,
+                )?)
+            })
+            .await?;
+        let mut result = vec![];
+        for event in events {
+            let (key, record) = decode(event)?;
+            if let Some(event_id) = self.event_id(&key) {
+                result.push((event_id.to_string(), record));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Counts the events with a single query, but reads them all outside of any namespace, whose
+    /// keys are those without a namespace separator.
+    async fn count(&self) -> Result<usize, OracleError> {
+        if self.prefix.is_empty() {
+            return Ok(self.get_all().await?.len());
+        }
+        let prefix = self.prefix.clone();
+        let count = self
+            .run(move |conn| Ok(dlc_storage_common::count_events_with_prefix(conn, &prefix)?))
+            .await?;
+        Ok(count as usize)
+    }
+
+    async fn remove(&self, event_id: String) -> Result<(), OracleError> {
+        let key = self.key(&event_id);
+        self.run(move |conn| {
+            dlc_storage_common::delete_event(conn, &key)?;
+            Ok(())
+        })
+        .await
+    }
+
+    /// Updates the records in a transaction which locks them until they are written, so that
+    /// concurrent updates of the same events, from any process, wait for it.
+    async fn update(
+        &self,
+        event_ids: Vec<String>,
+        update: RecordsUpdate,
+    ) -> Result<(), OracleError> {
+        let keys: Vec<String> = event_ids
+            .iter()
+            .map(|event_id| self.key(event_id))
+            .collect();
+        self.run(move |conn| {
+            conn.transaction(|conn| {
+                let mut stored = dlc_storage_common::lock_events(conn, &keys)?
+                    .into_iter()
+                    .map(decode)
+                    .collect::<Result<HashMap<_, _>, _>>()?;
+                let records = keys.iter().map(|key| stored.remove(key)).collect();
+                let events: Vec<NewEvent> = keys
+                    .into_iter()
+                    .zip(update(records)?)
+                    .filter_map(|(key, record)| {
+                        Some(NewEvent {
+                            event_id: key,
+                            content: base64::encode(record?),
+                        })
+                    })
+                    .collect();
+                if !events.is_empty() {
+                    dlc_storage_common::upsert_events(conn, events)?;
+                }
+                Ok(())
+            })
+        })
+        .await
+    }
+}
@@ -0,0 +1,151 @@
+use super::EventStore;
+use crate::{oracle::OracleError, AssetPair};
+use async_trait::async_trait;
+use log::info;
+use sled::{Batch, Config, Db, Tree};
+use std::ops::Bound::{Excluded, Unbounded};
+
+// name of the tree sled opens by default, which holds the events stored before namespaces
+const DEFAULT_TREE: &[u8] = b"__sled__default";
+
+/// Event store backed by a sled database, with one tree per asset pair.
+#[derive(Clone)]
+pub struct SledStore {
+    db: Db,
+    tree: Tree,
+}
+
+impl SledStore {
+    /// Opens the sled database at `path`, creating it if needed.
+    pub fn open(path: &str) -> Result<Self, OracleError> {
+        info!("creating sled event database at {}", path);
+        let db = Config::new()
+            .path(path)
+            .cache_capacity(128 * 1024 * 1024)
+            .open()?;
+        Ok(Self::new(db))
+    }
+
+    /// Returns the store of the already opened database `db`.
+    pub fn new(db: Db) -> Self {
+        Self {
+            tree: (*db).clone(),
+            db,
+        }
+    }
+}
+
+#[async_trait]
+impl EventStore for SledStore {
+    fn namespace(&self, asset_pair: &AssetPair) -> Result<Box<dyn EventStore>, OracleError> {
+        Ok(Box::new(Self {
+            db: self.db.clone(),
+            tree: self.db.open_tree(asset_pair.as_str())?,
+        }))
+    }
+
+    async fn namespaces(&self) -> Result<Vec<AssetPair>, OracleError> {
+        Ok(self
+            .db
+            .tree_names()
+            .into_iter()
+            .filter(|name| name.as_ref() != DEFAULT_TREE)
+            .filter_map(|name| String::from_utf8(name.to_vec()).ok()?.parse().ok())
+            .collect())
+    }
+
+    async fn insert(&self, event_id: String, record: Vec<u8>) -> Result<(), OracleError> {
+        self.tree.insert(event_id.into_bytes(), record)?;
+        Ok(())
+    }
+
+    async fn insert_all(&self, records: Vec<(String, Vec<u8>)>) -> Result<(), OracleError> {
+        let mut batch = Batch::default();
+        for (event_id, record) in records {
+            batch.insert(event_id.into_bytes(), record);
+        }
+        self.tree.apply_batch(batch)?;
+        Ok(())
+    }
+
+    async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError> {
+        Ok(self
+            .tree
+            .get(event_id.as_bytes())?
+            .map(|record| record.to_vec()))
+    }
+
+    async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        self.tree
+            .iter()
+            .map(|result| {
+                let (key, value) = result?;
+                Ok((String::from_utf8_lossy(&key).to_string(), value.to_vec()))
+            })
+            .collect()
+    }
+
+    async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let range = match (cursor, reverse) {
+            (Some(cursor), false) => self
+                .tree
+                .range::<Vec<u8>, _>((Excluded(cursor.into_bytes()), Unbounded)),
+            (Some(cursor), true) => self
+                .tree
+                .range::<Vec<u8>, _>((Unbounded, Excluded(cursor.into_bytes()))),
+            (None, _) => self.tree.iter(),
+        };
+        let range: Box<dyn Iterator<Item = _>> = if reverse {
+            Box::new(range.rev())
+        } else {
+            Box::new(range)
+        };
+        range
+            .take(limit)
+            .map(|result| {
+                let (key, value) = result?;
+                Ok((String::from_utf8_lossy(&key).to_string(), value.to_vec()))
+            })
+            .collect()
+    }
+
+    async fn count(&self) -> Result<usize, OracleError> {
+        Ok(self.tree.len())
+    }
+
+    async fn remove(&self, event_id: String) -> Result<(), OracleError> {
+        self.tree.remove(event_id.as_bytes())?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::EventHandler;
+
+    #[tokio::test]
+    async fn inserts_batches() {
+        let db = Config::new().temporary(true).open().unwrap();
+        let handler = EventHandler::with_store(SledStore::new(db))
+            .for_asset_pair(&"BTCUSD".parse().unwrap())
+            .unwrap();
+
+        handler
+            .insert_all(vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])])
+            .await
+            .unwrap();
+        handler.insert_all(vec![]).await.unwrap();
+        let mut events = handler.get_all().await.unwrap();
+        events.sort();
+        assert_eq!(
+            vec![("a".to_string(), vec![1]), ("b".to_string(), vec![2])],
+            events
+        );
+    }
+}
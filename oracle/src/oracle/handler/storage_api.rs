@@ -0,0 +1,157 @@
+extern crate base64;
+use super::{EventStore, NAMESPACE_SEPARATOR};
+use crate::{oracle::OracleError, AssetPair};
+use async_trait::async_trait;
+use dlc_clients::{Event, NewEvent, StorageApiClient, UpdateEvent};
+
+/// Event store backed by the storage api, the event ids of each asset pair being prefixed with
+/// the asset pair.
+#[derive(Clone)]
+pub struct StorageApiConn {
+    pub client: StorageApiClient,
+
+    // prepended to event ids to form the storage api keys, empty outside of any namespace
+    prefix: String,
+}
+
+impl StorageApiConn {
+    pub fn new(endpoint: String) -> Self {
+        Self {
+            client: StorageApiClient::new(endpoint),
+            prefix: String::new(),
+        }
+    }
+
+    // returns the event id of storage api key `key` in this namespace, if it is in it
+    fn event_id<'a>(&self, key: &'a str) -> Option<&'a str> {
+        if self.prefix.is_empty() && key.contains(NAMESPACE_SEPARATOR) {
+            return None;
+        }
+        key.strip_prefix(&self.prefix)
+    }
+}
+
+// returns the record of storage api event `event`
+fn decode(event: &Event) -> Result<Vec<u8>, OracleError> {
+    base64::decode(&event.content)
+        .map_err(|_| OracleError::InvalidRecordError(event.event_id.clone()))
+}
+
+#[async_trait]
+impl EventStore for StorageApiConn {
+    fn namespace(&self, asset_pair: &AssetPair) -> Result<Box<dyn EventStore>, OracleError> {
+        Ok(Box::new(Self {
+            client: self.client.clone(),
+            prefix: format!("{}{}", asset_pair, NAMESPACE_SEPARATOR),
+        }))
+    }
+
+    async fn namespaces(&self) -> Result<Vec<AssetPair>, OracleError> {
+        Ok(self
+            .client
+            .get_events()
+            .await?
+            .into_iter()
+            .filter_map(|event| {
+                let (asset_pair, _) = event.event_id.split_once(NAMESPACE_SEPARATOR)?;
+                asset_pair.parse().ok()
+            })
+            .collect())
+    }
+
+    async fn insert(&self, event_id: String, record: Vec<u8>) -> Result<(), OracleError> {
+        let event_id = format!("{}{}", self.prefix, event_id);
+        let content = base64::encode(record);
+        if self.client.get_event(event_id.clone()).await?.is_some() {
+            self.client
+                .update_event(
+                    event_id,
+                    UpdateEvent {
+                        content: Some(content),
+                    },
+                )
+                .await?;
+        } else {
+            self.client
+                .create_event(NewEvent { event_id, content })
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Creates or updates all `records` with a single storage api call.
+    async fn insert_all(&self, records: Vec<(String, Vec<u8>)>) -> Result<(), OracleError> {
+        let events = records
+            .into_iter()
+            .map(|(event_id, record)| NewEvent {
+                event_id: format!("{}{}", self.prefix, event_id),
+                content: base64::encode(record),
+            })
+            .collect();
+        self.client.upsert_events(events).await?;
+        Ok(())
+    }
+
+    async fn get(&self, event_id: String) -> Result<Option<Vec<u8>>, OracleError> {
+        let event = self
+            .client
+            .get_event(format!("{}{}", self.prefix, event_id))
+            .await?;
+        match event {
+            Some(event) => Ok(Some(decode(&event)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_all(&self) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let res_events = self.client.get_events().await?;
+        let mut result: Vec<(String, Vec<u8>)> = vec![];
+        for event in res_events {
+            let event_id = match self.event_id(&event.event_id) {
+                Some(event_id) => event_id.to_string(),
+                None => continue,
+            };
+            result.push((event_id, decode(&event)?));
+        }
+        Ok(result)
+    }
+
+    /// Reads the page only, with a single storage api call.
+    async fn get_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<(String, Vec<u8>)>, OracleError> {
+        let cursor = cursor.map(|cursor| format!("{}{}", self.prefix, cursor));
+        let res_events = self
+            .client
+            .get_events_page(&self.prefix, cursor, limit, reverse)
+            .await?;
+        let mut result: Vec<(String, Vec<u8>)> = vec![];
+        for event in res_events {
+            let event_id = match self.event_id(&event.event_id) {
+                Some(event_id) => event_id.to_string(),
+                None => continue,
+            };
+            result.push((event_id, decode(&event)?));
+        }
+        Ok(result)
+    }
+
+    /// Counts the events with a single storage api call, but reads them all outside of any
+    /// namespace, whose keys are those without a namespace separator.
+    async fn count(&self) -> Result<usize, OracleError> {
+        if self.prefix.is_empty() {
+            return Ok(self.get_all().await?.len());
+        }
+        Ok(self.client.count_events(&self.prefix).await? as usize)
+    }
+
+    async fn remove(&self, event_id: String) -> Result<(), OracleError> {
+        self.client
+            .delete_event(format!("{}{}", self.prefix, event_id))
+            .await?;
+        Ok(())
+    }
+}
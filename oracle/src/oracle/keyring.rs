@@ -0,0 +1,106 @@
+use secp256k1_zkp::{KeyPair, XOnlyPublicKey as SchnorrPublicKey};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// A key of the oracle and the time window it announced events in.
+#[derive(Clone)]
+pub struct OracleKey {
+    pub keypair: KeyPair,
+    /// start of the window, unknown for the first key of the oracle
+    pub activated_at: Option<OffsetDateTime>,
+    /// end of the window, `None` for the active key
+    pub retired_at: Option<OffsetDateTime>,
+}
+
+/// A key retired by a rotation, as persisted along with the active key.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct RetiredKey {
+    pub secret_key: String,
+    #[serde(with = "time::serde::rfc3339")]
+    pub retired_at: OffsetDateTime,
+}
+
+/// The keys of the oracle: the active key, which announces new events, and the keys it replaced,
+/// which are kept to attest to the events they announced.
+#[derive(Clone)]
+pub struct Keyring {
+    // ordered by activation, the active key last
+    keys: Vec<OracleKey>,
+}
+
+impl Keyring {
+    /// Creates the keyring of `active` and of the keys it replaced, given with the time they were
+    /// retired. Each key is active from the retirement of the previous one.
+    pub fn new(active: KeyPair, mut retired: Vec<(KeyPair, OffsetDateTime)>) -> Self {
+        retired.sort_by_key(|(_, retired_at)| *retired_at);
+        // a rotation interrupted before storing the new key leaves the active key retired
+        retired.retain(|(keypair, _)| keypair.public_key() != active.public_key());
+
+        let mut activated_at = None;
+        let mut keys = Vec::with_capacity(retired.len() + 1);
+        for (keypair, retired_at) in retired {
+            keys.push(OracleKey {
+                keypair,
+                activated_at,
+                retired_at: Some(retired_at),
+            });
+            activated_at = Some(retired_at);
+        }
+        keys.push(OracleKey {
+            keypair: active,
+            activated_at,
+            retired_at: None,
+        });
+        Keyring { keys }
+    }
+
+    /// Returns the key new events are announced with.
+    pub fn active(&self) -> &KeyPair {
+        &self.keys.last().expect("keyring has an active key").keypair
+    }
+
+    /// Returns the key of the keyring with public key `public_key`, if any.
+    pub fn get(&self, public_key: &SchnorrPublicKey) -> Option<&KeyPair> {
+        self.keys
+            .iter()
+            .map(|key| &key.keypair)
+            .find(|keypair| keypair.public_key() == *public_key)
+    }
+
+    /// Returns all keys of the keyring, ordered by activation, the active key last.
+    pub fn keys(&self) -> &[OracleKey] {
+        &self.keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1_zkp::Secp256k1;
+
+    #[test]
+    fn orders_keys_by_validity() {
+        let secp = Secp256k1::new();
+        let keypair = |byte| KeyPair::from_seckey_slice(&secp, &[byte; 32]).unwrap();
+        let first_rotation = OffsetDateTime::from_unix_timestamp(1000).unwrap();
+        let second_rotation = OffsetDateTime::from_unix_timestamp(2000).unwrap();
+        let keyring = Keyring::new(
+            keypair(3),
+            vec![
+                (keypair(2), second_rotation),
+                (keypair(1), first_rotation),
+                (keypair(3), second_rotation),
+            ],
+        );
+
+        assert_eq!(3, keyring.keys().len());
+        assert_eq!(keypair(3).public_key(), keyring.active().public_key());
+        assert_eq!(None, keyring.keys()[0].activated_at);
+        assert_eq!(Some(first_rotation), keyring.keys()[0].retired_at);
+        assert_eq!(Some(first_rotation), keyring.keys()[1].activated_at);
+        assert_eq!(Some(second_rotation), keyring.keys()[2].activated_at);
+        assert_eq!(None, keyring.keys()[2].retired_at);
+        assert!(keyring.get(&keypair(1).public_key()).is_some());
+        assert!(keyring.get(&keypair(4).public_key()).is_none());
+    }
+}
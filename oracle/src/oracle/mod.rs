@@ -1,39 +1,63 @@
-use crate::OracleConfig;
+use crate::{
+    oracle::pricefeeds::{PriceAggregator, PriceFeed},
+    AssetPairInfo, OracleConfig, OracleEventDescriptor, OutOfRangePolicy,
+};
+use dlc_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use log::{info, warn};
 use secp256k1_zkp::All;
-use secp256k1_zkp::KeyPair;
 use secp256k1_zkp::Secp256k1;
-use serde::{Deserialize, Serialize};
+use secp256k1_zkp::XOnlyPublicKey as SchnorrPublicKey;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::{io::Cursor, sync::Arc};
+use time::OffsetDateTime;
+use tokio::sync::Mutex;
 
 mod error;
+mod export;
 mod handler;
-use crate::oracle::handler::EventHandler;
+mod keyring;
+mod stored_event;
+#[cfg(test)]
+mod test_utils;
+pub use crate::oracle::handler::{
+    postgres_database_url, sled_path, storage_api_enabled, storage_api_endpoint, EventHandler,
+    EventStore, MemoryStore, RecordsUpdate, SledStore, StorageApiConn,
+};
+#[cfg(feature = "postgres")]
+pub use crate::oracle::handler::{postgres_migrate, postgres_pool_size, PostgresStore};
 pub use error::OracleError;
 pub use error::Result;
+pub use export::{
+    read_export, write_export, ExportHeader, ExportRecord, ExportedEvent, EXPORT_FORMAT_VERSION,
+};
+pub use keyring::{Keyring, OracleKey, RetiredKey};
+pub use stored_event::{StoredEvent, STORED_EVENT_VERSION};
 
-#[derive(Clone, Deserialize, Serialize)]
-// outstanding_sk_nonces?, suredbits_announcement, suredbits_attestation?, announcement, attestation?, outcome?, uuid
-pub struct DbValue(
-    pub Option<Vec<[u8; 32]>>,
-    pub Vec<u8>,
-    pub Option<Vec<u8>>,
-    pub Vec<u8>,
-    pub Option<Vec<u8>>,
-    pub Option<u64>,
-    pub String,
-);
+use signer::{Signer, SigningRequest};
 
 #[derive(Clone)]
 pub struct Oracle {
     pub oracle_config: OracleConfig,
+    pub asset_pair_info: AssetPairInfo,
     pub event_handler: EventHandler,
-    keypair: KeyPair,
+    pub pricefeed: Arc<PriceAggregator>,
+    signer: Arc<dyn Signer>,
     secp: Secp256k1<All>,
+    // held while creating or attesting events, so that concurrent requests cannot both announce
+    // the same event or sign with the same nonces
+    write_lock: Arc<Mutex<()>>,
 }
 
 impl Oracle {
+    /// Creates the oracle of `asset_pair_info`, storing its events with `event_handler`, which
+    /// should be dedicated to the asset pair, see [`EventHandler::for_asset_pair`]. Events are
+    /// announced with the active key of `signer`, and attested with the key which announced them.
     pub fn new(
         oracle_config: OracleConfig,
-        keypair: KeyPair,
+        asset_pair_info: AssetPairInfo,
+        event_handler: EventHandler,
+        signer: Arc<dyn Signer>,
         secp: Secp256k1<All>,
     ) -> Result<Oracle> {
         if !oracle_config.announcement_offset.is_positive() {
@@ -41,26 +65,897 @@ impl Oracle {
                 oracle_config.announcement_offset,
             ));
         }
-        let event_handler = EventHandler::new();
+        if !oracle_config.frequency.is_positive() {
+            return Err(OracleError::InvalidFrequencyError(oracle_config.frequency));
+        }
+        let pricefeeds = asset_pair_info
+            .pricefeeds
+            .iter()
+            .map(|pricefeed| pricefeed.build())
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let pricefeed = Arc::new(PriceAggregator::new(
+            pricefeeds,
+            asset_pair_info.price_aggregation,
+        ));
 
         Ok(Oracle {
             oracle_config,
+            asset_pair_info,
             event_handler,
-            keypair,
+            pricefeed,
+            signer,
             secp,
+            write_lock: Arc::new(Mutex::new(())),
         })
     }
 
-    pub fn get_keypair(&self) -> &KeyPair {
-        &self.keypair
+    /// Returns the active key of the oracle, which announces new events.
+    pub fn public_key(&self) -> SchnorrPublicKey {
+        self.signer.public_key()
+    }
+    pub fn get_signer(&self) -> &Arc<dyn Signer> {
+        &self.signer
     }
     pub fn get_secp(&self) -> &Secp256k1<All> {
         &self.secp
     }
+
+    /// Returns the stored event with id `uuid`, if any.
+    pub async fn get_event(&self, uuid: String) -> Result<Option<StoredEvent>> {
+        match self.event_handler.get(uuid).await? {
+            Some(record) => Ok(Some(
+                StoredEvent::from_record(&record, &self.asset_pair_info.asset_pair)?.0,
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Returns all stored events of this oracle's asset pair.
+    pub async fn get_events(&self) -> Result<Vec<StoredEvent>> {
+        self.event_handler
+            .get_all()
+            .await?
+            .iter()
+            .map(|(_, record)| {
+                Ok(StoredEvent::from_record(record, &self.asset_pair_info.asset_pair)?.0)
+            })
+            .collect()
+    }
+
+    /// Returns the number of stored events of this oracle's asset pair, without reading them.
+    pub async fn count_events(&self) -> Result<usize> {
+        self.event_handler.count().await
+    }
+
+    /// Returns a page of at most `limit` stored events of this oracle's asset pair, in order of
+    /// uuid, see [`EventStore::get_page`].
+    pub async fn get_events_page(
+        &self,
+        cursor: Option<String>,
+        limit: usize,
+        reverse: bool,
+    ) -> Result<Vec<StoredEvent>> {
+        self.event_handler
+            .get_page(cursor, limit, reverse)
+            .await?
+            .iter()
+            .map(|(_, record)| {
+                Ok(StoredEvent::from_record(record, &self.asset_pair_info.asset_pair)?.0)
+            })
+            .collect()
+    }
+
+    async fn store_event(&self, event: &StoredEvent) -> Result<()> {
+        self.event_handler
+            .insert(event.uuid.clone(), event.to_record()?)
+            .await
+    }
+
+    async fn store_events(&self, events: impl Iterator<Item = &StoredEvent>) -> Result<()> {
+        let records = events
+            .map(|event| Ok((event.uuid.clone(), event.to_record()?)))
+            .collect::<Result<Vec<_>>>()?;
+        self.event_handler.insert_all(records).await
+    }
+
+    /// Upgrades the stored events of older record versions to [`StoredEvent`]s of the current
+    /// version in place, returning how many were upgraded.
+    pub async fn migrate_stored_events(&self) -> Result<usize> {
+        let _write_guard = self.write_lock.lock().await;
+        let mut migrated = 0;
+        for (uuid, record) in self.event_handler.get_all().await? {
+            let (event, upgraded) =
+                StoredEvent::from_record(&record, &self.asset_pair_info.asset_pair)?;
+            if upgraded {
+                info!("upgrading stored event {}", uuid);
+                self.store_event(&event).await?;
+                migrated += 1;
+            }
+        }
+        Ok(migrated)
+    }
+
+    /// Stores `events`, e.g. as returned by [`Oracle::get_events`] from another event database,
+    /// returning how many were stored. Stored events are kept as they are unless `overwrite` is
+    /// set. Either all events are stored or none is.
+    ///
+    /// Even with `overwrite`, a stored event is only replaced by the same announcement, attested
+    /// the same way if it is attested: nonces are derived from the uuid, so an event announced or
+    /// attested again differently could be attested twice, which reveals the oracle key.
+    /// [`OracleError::EventConflictError`] is returned otherwise.
+    pub async fn import_events(&self, events: Vec<StoredEvent>, overwrite: bool) -> Result<usize> {
+        let _write_guard = self.write_lock.lock().await;
+        let mut imported = Vec::with_capacity(events.len());
+        for event in events {
+            if event.asset_pair != self.asset_pair_info.asset_pair {
+                return Err(OracleError::AssetPairMismatchError(
+                    event.uuid,
+                    event.asset_pair,
+                ));
+            }
+            if let Some(stored) = self.get_event(event.uuid.clone()).await? {
+                if !overwrite {
+                    info!("skipping event {}, which is already stored", event.uuid);
+                    continue;
+                }
+                if stored.announcement != event.announcement
+                    || stored.attestation.is_some() && stored.attestation != event.attestation
+                {
+                    return Err(OracleError::EventConflictError(event.uuid));
+                }
+            }
+            imported.push(event);
+        }
+        self.store_events(imported.iter()).await?;
+        Ok(imported.len())
+    }
+
+    /// Announces a new event with id `uuid` maturing at `maturation` and stores it.
+    ///
+    /// The event is described by `event_descriptor`, or by the event descriptor of this oracle's
+    /// asset pair if `None`. `metadata` is stored and returned with the event as is.
+    ///
+    /// Events are immutable: if an event with id `uuid` already exists, it is returned unchanged.
+    /// When an `idempotency_key` is given, the existing event is only returned if it was created
+    /// with the same key, and [`OracleError::IdempotencyKeyConflictError`] is returned otherwise.
+    pub async fn create_event(
+        &self,
+        uuid: String,
+        maturation: OffsetDateTime,
+        event_descriptor: Option<OracleEventDescriptor>,
+        metadata: Option<serde_json::Map<String, serde_json::Value>>,
+        idempotency_key: Option<String>,
+    ) -> Result<StoredEvent> {
+        let _write_guard = self.write_lock.lock().await;
+        let existing = self.get_event(uuid.clone()).await?;
+        let (event, announced) = self
+            .announce(
+                EventToCreate {
+                    uuid,
+                    maturation,
+                    event_descriptor,
+                    metadata,
+                    idempotency_key,
+                },
+                existing,
+            )
+            .await?;
+        if announced {
+            info!("Inserting new event ...[uuid: {}]", event.uuid);
+            self.store_event(&event).await?;
+        }
+        Ok(event)
+    }
+
+    /// Announces each of `events` like [`Oracle::create_event`], and stores the new ones at once.
+    ///
+    /// Returns the result of each event, in order. Events which cannot be created fail on their
+    /// own, but if storing fails none of the new events is stored and an error is returned.
+    pub async fn create_events(
+        &self,
+        events: Vec<EventToCreate>,
+    ) -> Result<Vec<Result<StoredEvent>>> {
+        let _write_guard = self.write_lock.lock().await;
+        let mut results = Vec::with_capacity(events.len());
+        // events announced earlier in the batch are not stored yet
+        let mut announced: HashMap<String, StoredEvent> = HashMap::new();
+        for event in events {
+            let existing = match announced.get(&event.uuid) {
+                Some(existing) => Ok(Some(existing.clone())),
+                None => self.get_event(event.uuid.clone()).await,
+            };
+            let result = match existing {
+                Ok(existing) => self.announce(event, existing).await,
+                Err(e) => Err(e),
+            };
+            results.push(result.map(|(event, is_new)| {
+                if is_new {
+                    announced.insert(event.uuid.clone(), event.clone());
+                }
+                event
+            }));
+        }
+
+        info!("Inserting {} new events", announced.len());
+        self.store_events(announced.values()).await?;
+        Ok(results)
+    }
+
+    // returns the announced event and whether it is new, or `existing` if the event exists
+    async fn announce(
+        &self,
+        event: EventToCreate,
+        existing: Option<StoredEvent>,
+    ) -> Result<(StoredEvent, bool)> {
+        let EventToCreate {
+            uuid,
+            maturation,
+            event_descriptor,
+            metadata,
+            idempotency_key,
+        } = event;
+        // existing events are returned as announced, even if their descriptor would not be valid
+        // anymore
+        if let Some(event) = existing {
+            if idempotency_key.is_some() && event.idempotency_key != idempotency_key {
+                return Err(OracleError::IdempotencyKeyConflictError(uuid));
+            }
+            info!(
+                "event with uuid {} already exists, not announcing it again",
+                uuid
+            );
+            return Ok((event, false));
+        }
+
+        let event_descriptor = event_descriptor
+            .unwrap_or_else(|| self.asset_pair_info.event_descriptor.clone().into());
+        validate_event_descriptor(&event_descriptor)?;
+
+        // signers may block, e.g. on a remote signer, so they are called off the async runtime
+        let signer = self.signer.clone();
+        let asset_pair = self.asset_pair_info.asset_pair.clone();
+        let (event_id, descriptor) = (uuid.clone(), event_descriptor.clone());
+        let announcement_obj = tokio::task::spawn_blocking(move || {
+            oracle_scheduler::build_announcement(
+                signer.as_ref(),
+                &asset_pair,
+                maturation,
+                event_id,
+                descriptor,
+            )
+        })
+        .await??;
+
+        // nonces are derived again when attesting, so they are not stored
+        let event = StoredEvent {
+            version: STORED_EVENT_VERSION,
+            uuid,
+            asset_pair: self.asset_pair_info.asset_pair.clone(),
+            event_descriptor,
+            maturation,
+            announcing_key: announcement_obj.oracle_pubkey.to_string(),
+            created_at: Some(OffsetDateTime::now_utc()),
+            outstanding_sk_nonces: None,
+            announcement: announcement_obj.encode(),
+            suredbits_announcement: announcement_obj.suredbits_encode(),
+            attestation: None,
+            suredbits_attestation: None,
+            outcome: None,
+            unclamped_outcome: None,
+            attested_at: None,
+            early_attestation_reason: None,
+            metadata,
+            idempotency_key,
+            attestation_idempotency_key: None,
+        };
+        Ok((event, true))
+    }
+
+    /// Attests to `outcome` for the stored event with id `uuid`.
+    ///
+    /// For digit decomposition events `outcome` is the decimal number to attest to, for enum
+    /// events it must be one of the announced outcomes. An event is only ever attested once:
+    /// attesting again to the same outcome returns the stored attestation, and attesting to a
+    /// different outcome fails with [`OracleError::AlreadyAttestedError`].
+    ///
+    /// Events are only attested from their maturation on, minus the configured
+    /// `attestation_grace_period`, unless an `early_attestation_reason` is given, which is logged
+    /// and stored with the event for audit.
+    ///
+    /// A request retried with the `idempotency_key` of the request which attested the event
+    /// returns the stored attestation, whatever its outcome.
+    pub async fn attest(
+        &self,
+        uuid: String,
+        outcome: String,
+        early_attestation_reason: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<StoredEvent> {
+        let _write_guard = self.write_lock.lock().await;
+        info!("retrieving oracle event with uuid {}", uuid);
+        let oracle = self.clone();
+        self.event_handler
+            .update(vec![uuid], move |records| {
+                let record = records
+                    .into_iter()
+                    .next()
+                    .flatten()
+                    .ok_or(OracleError::EventNotFoundError)?;
+                let event =
+                    StoredEvent::from_record(&record, &oracle.asset_pair_info.asset_pair)?.0;
+                let (event, updated) =
+                    oracle.sign(event, outcome, early_attestation_reason, idempotency_key)?;
+                let record = if updated {
+                    Some(event.to_record()?)
+                } else {
+                    None
+                };
+                Ok((vec![record], event))
+            })
+            .await
+    }
+
+    /// Attests each of `attestations` like [`Oracle::attest`], or like
+    /// [`Oracle::attest_with_pricefeeds`] when no outcome is given, and stores the attested events
+    /// at once.
+    ///
+    /// Returns the result of each attestation, in order. Attestations which cannot be made fail on
+    /// their own, but if storing fails none of the attestations is stored and an error is returned.
+    pub async fn attest_events(
+        &self,
+        attestations: Vec<EventToAttest>,
+    ) -> Result<Vec<Result<StoredEvent>>> {
+        // prices are retrieved before locking, as for single attestations
+        let mut outcomes = Vec::with_capacity(attestations.len());
+        for attestation in &attestations {
+            outcomes.push(match &attestation.outcome {
+                Some(outcome) => Ok(Some(outcome.clone())),
+                None => self
+                    .outcome_from_pricefeeds(&attestation.uuid, &attestation.idempotency_key)
+                    .await
+                    .map(|outcome| outcome.map(|outcome| outcome.to_string())),
+            });
+        }
+
+        let _write_guard = self.write_lock.lock().await;
+        let mut uuids: Vec<String> = vec![];
+        for attestation in &attestations {
+            if !uuids.contains(&attestation.uuid) {
+                uuids.push(attestation.uuid.clone());
+            }
+        }
+        let oracle = self.clone();
+        let event_ids = uuids.clone();
+        self.event_handler
+            .update(event_ids, move |records| {
+                let stored: HashMap<String, Option<Vec<u8>>> =
+                    uuids.iter().cloned().zip(records).collect();
+                let mut results = Vec::with_capacity(attestations.len());
+                // events attested earlier in the batch are not stored yet
+                let mut updated: HashMap<String, StoredEvent> = HashMap::new();
+                for (attestation, outcome) in attestations.into_iter().zip(outcomes) {
+                    let event = match updated.get(&attestation.uuid) {
+                        Some(event) => Ok(event.clone()),
+                        None => match stored.get(&attestation.uuid).and_then(Option::as_ref) {
+                            Some(record) => {
+                                StoredEvent::from_record(record, &oracle.asset_pair_info.asset_pair)
+                                    .map(|(event, _)| event)
+                            }
+                            None => Err(OracleError::EventNotFoundError),
+                        },
+                    };
+                    let result = event.and_then(|event| match outcome? {
+                        Some(outcome) => oracle.sign(
+                            event,
+                            outcome,
+                            attestation.early_attestation_reason,
+                            attestation.idempotency_key,
+                        ),
+                        // the request is retried, see `outcome_from_pricefeeds`
+                        None => Ok((event, false)),
+                    });
+                    results.push(result.map(|(event, is_updated)| {
+                        if is_updated {
+                            updated.insert(event.uuid.clone(), event.clone());
+                        }
+                        event
+                    }));
+                }
+
+                let records = uuids
+                    .iter()
+                    .map(|uuid| updated.get(uuid).map(StoredEvent::to_record).transpose())
+                    .collect::<Result<Vec<_>>>()?;
+                Ok((records, results))
+            })
+            .await
+    }
+
+    // returns the attested event and whether it was updated, and so must be stored
+    fn sign(
+        &self,
+        mut event: StoredEvent,
+        outcome: String,
+        early_attestation_reason: Option<String>,
+        idempotency_key: Option<String>,
+    ) -> Result<(StoredEvent, bool)> {
+        let uuid = event.uuid.clone();
+        if is_retried_attestation(&event, &idempotency_key) {
+            info!(
+                "event with uuid {} is already attested by this request",
+                uuid
+            );
+            return Ok((event, false));
+        }
+        let (outcomes, numeric_outcome, unclamped_outcome) = match &event.event_descriptor {
+            OracleEventDescriptor::DigitDecompositionEvent(event_descriptor) => {
+                let outcome = outcome
+                    .parse::<i64>()
+                    .map_err(|_| OracleError::InvalidOutcomeError(outcome.clone()))?;
+                // Here, we take the outcome of the DLC and break it down into one digit per nonce,
+                // preceded by its sign for signed events
+                match event_descriptor.decompose(outcome) {
+                    Some(outcomes) => (outcomes, Some(outcome), None),
+                    None => match self.asset_pair_info.out_of_range_policy {
+                        OutOfRangePolicy::Reject => {
+                            return Err(OracleError::OutcomeOutOfRangeError(outcome))
+                        }
+                        OutOfRangePolicy::ClampToMax => {
+                            let clamped = outcome.clamp(
+                                event_descriptor.min_outcome(),
+                                event_descriptor.max_outcome(),
+                            );
+                            warn!(
+                                "clamping outcome {} of event {} to {}",
+                                outcome, uuid, clamped
+                            );
+                            let outcomes = event_descriptor
+                                .decompose(clamped)
+                                .ok_or(OracleError::OutcomeOutOfRangeError(clamped))?;
+                            (outcomes, Some(clamped), Some(outcome))
+                        }
+                    },
+                }
+            }
+            OracleEventDescriptor::EnumEvent(event_descriptor) => {
+                if !event_descriptor.outcomes.contains(&outcome) {
+                    return Err(OracleError::InvalidOutcomeError(outcome));
+                }
+                (vec![outcome], None, None)
+            }
+        };
+
+        if let Some(attestation) = &event.attestation {
+            // signing a different outcome with the same nonces would reveal the oracle's key
+            if decode_attestation(attestation)?.outcomes != outcomes {
+                return Err(OracleError::AlreadyAttestedError(uuid));
+            }
+            info!("event with uuid {} is already attested", uuid);
+            let cleared = event.outstanding_sk_nonces.take().is_some();
+            if cleared {
+                info!("clearing stored nonces of attested event {}", uuid);
+            }
+            return Ok((event, cleared));
+        }
+
+        let now = OffsetDateTime::now_utc();
+        if now < event.maturation - self.oracle_config.attestation_grace_period {
+            match &early_attestation_reason {
+                Some(reason) => warn!(
+                    "attesting event {} {} before maturation, reason: {}",
+                    uuid,
+                    event.maturation - now,
+                    reason
+                ),
+                None => {
+                    return Err(OracleError::PrematureAttestationError(
+                        uuid,
+                        event.maturation - now,
+                    ))
+                }
+            }
+        }
+
+        // events are attested with the key which announced them, even if it was retired since
+        if !SchnorrPublicKey::from_str(&event.announcing_key)
+            .map(|public_key| {
+                self.signer
+                    .keys()
+                    .iter()
+                    .any(|key| key.public_key == public_key)
+            })
+            .unwrap_or(false)
+        {
+            return Err(OracleError::UnknownOracleKeyError(
+                event.announcing_key.clone(),
+            ));
+        }
+        // each outcome is signed with its own nonce, a missing one would change the outcome
+        if event.event_descriptor.nb_nonces() != outcomes.len() {
+            return Err(OracleError::NonceMismatchError(uuid));
+        }
+
+        // events announced before nonces were derived have their nonces stored, otherwise the
+        // signer derives them again
+        let request = SigningRequest {
+            asset_pair: self.asset_pair_info.asset_pair.clone(),
+            announcement: event.announcement.clone(),
+            outcomes,
+            early_attestation_reason: early_attestation_reason.clone(),
+            sk_nonces: event.outstanding_sk_nonces.take(),
+        };
+        let attestation = oracle_scheduler::build_attestation(self.signer.as_ref(), &request)?;
+
+        // storing the attestation also clears the stored nonces of older events, if any
+        event.attestation = Some(attestation.encode());
+        event.suredbits_attestation = Some(attestation.suredbits_encode());
+        event.outcome = numeric_outcome;
+        event.unclamped_outcome = unclamped_outcome;
+        event.attested_at = Some(now);
+        event.early_attestation_reason = early_attestation_reason;
+        event.attestation_idempotency_key = idempotency_key;
+
+        info!(
+            "attesting with uuid {} and attestation {:#?}",
+            uuid, attestation
+        );
+        Ok((event, true))
+    }
+
+    /// Retrieves the price of this oracle's asset pair at `maturation` from its pricefeeds, as an
+    /// outcome to attest to with the given `precision`, i.e. the price is `outcome * 10^precision`.
+    pub async fn retrieve_outcome(
+        &self,
+        maturation: OffsetDateTime,
+        precision: i32,
+    ) -> Result<i64> {
+        let price = self
+            .pricefeed
+            .retrieve_price(&self.asset_pair_info.asset_pair, maturation)
+            .await?;
+        if !price.is_finite() {
+            return Err(OracleError::InvalidPriceError(price));
+        }
+        // prices beyond the range of i64 saturate, and are then rejected or clamped like any
+        // outcome which does not fit in the announced digits
+        Ok((price / 10f64.powi(precision)).round() as i64)
+    }
+
+    /// Attests to the price reported by the pricefeeds at the maturation of the stored event with
+    /// id `uuid`, see [`Oracle::attest`].
+    pub async fn attest_with_pricefeeds(
+        &self,
+        uuid: String,
+        idempotency_key: Option<String>,
+    ) -> Result<StoredEvent> {
+        match self
+            .outcome_from_pricefeeds(&uuid, &idempotency_key)
+            .await?
+        {
+            Some(outcome) => {
+                self.attest(uuid, outcome.to_string(), None, idempotency_key)
+                    .await
+            }
+            None => self
+                .get_event(uuid)
+                .await?
+                .ok_or(OracleError::EventNotFoundError),
+        }
+    }
+
+    // returns the outcome of event `uuid` reported by the pricefeeds, or `None` if the event was
+    // already attested by this request, as the pricefeeds may report another price by now
+    async fn outcome_from_pricefeeds(
+        &self,
+        uuid: &str,
+        idempotency_key: &Option<String>,
+    ) -> Result<Option<i64>> {
+        let event = self
+            .get_event(uuid.to_string())
+            .await?
+            .ok_or(OracleError::EventNotFoundError)?;
+        if is_retried_attestation(&event, idempotency_key) {
+            return Ok(None);
+        }
+        let precision = match &event.event_descriptor {
+            OracleEventDescriptor::DigitDecompositionEvent(e) => e.precision,
+            OracleEventDescriptor::EnumEvent(_) => {
+                return Err(OracleError::UnexpectedEventDescriptorError)
+            }
+        };
+        Ok(Some(
+            self.retrieve_outcome(event.maturation, precision).await?,
+        ))
+    }
+}
+
+/// An event to announce, see [`Oracle::create_event`].
+#[derive(Clone, Debug)]
+pub struct EventToCreate {
+    pub uuid: String,
+    pub maturation: OffsetDateTime,
+    pub event_descriptor: Option<OracleEventDescriptor>,
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    pub idempotency_key: Option<String>,
+}
+
+/// An event to attest, to the price reported by the pricefeeds if `outcome` is `None`, see
+/// [`Oracle::attest`].
+#[derive(Clone, Debug)]
+pub struct EventToAttest {
+    pub uuid: String,
+    pub outcome: Option<String>,
+    pub early_attestation_reason: Option<String>,
+    pub idempotency_key: Option<String>,
+}
+
+fn is_retried_attestation(event: &StoredEvent, idempotency_key: &Option<String>) -> bool {
+    event.attestation.is_some()
+        && idempotency_key.is_some()
+        && &event.attestation_idempotency_key == idempotency_key
+}
+
+fn validate_event_descriptor(event_descriptor: &OracleEventDescriptor) -> Result<()> {
+    match event_descriptor {
+        OracleEventDescriptor::DigitDecompositionEvent(event_descriptor) => {
+            if event_descriptor.base < 2 {
+                return Err(OracleError::InvalidEventDescriptorError(format!(
+                    "base must be at least 2, got {}",
+                    event_descriptor.base
+                )));
+            }
+            if event_descriptor.num_digits == 0 {
+                return Err(OracleError::InvalidEventDescriptorError(
+                    "num_digits must be positive".to_string(),
+                ));
+            }
+        }
+        OracleEventDescriptor::EnumEvent(event_descriptor) => {
+            if event_descriptor.outcomes.is_empty() {
+                return Err(OracleError::InvalidEventDescriptorError(
+                    "enum events need at least one outcome".to_string(),
+                ));
+            }
+            if event_descriptor.outcomes.iter().any(String::is_empty) {
+                return Err(OracleError::InvalidEventDescriptorError(
+                    "outcomes must not be empty".to_string(),
+                ));
+            }
+            let mut outcomes = event_descriptor.outcomes.clone();
+            outcomes.sort();
+            outcomes.dedup();
+            if outcomes.len() != event_descriptor.outcomes.len() {
+                return Err(OracleError::InvalidEventDescriptorError(
+                    "outcomes must be unique".to_string(),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn announced_event_descriptor(announcement: &OracleAnnouncement) -> OracleEventDescriptor {
+    (&announcement.oracle_event.event_descriptor).into()
+}
+
+/// Decodes an `oracle_announcement` TLV.
+pub fn decode_announcement(announcement: &[u8]) -> Result<OracleAnnouncement> {
+    let mut announcement_cursor = Cursor::new(announcement);
+    <OracleAnnouncement as lightning::util::ser::Readable>::read(&mut announcement_cursor)
+        .map_err(|e| OracleError::AnnouncementDecodeError(format!("{:?}", e)))
+}
+
+/// Decodes an `oracle_attestation` TLV.
+pub fn decode_attestation(attestation: &[u8]) -> Result<OracleAttestation> {
+    let mut attestation_cursor = Cursor::new(attestation);
+    <OracleAttestation as lightning::util::ser::Readable>::read(&mut attestation_cursor)
+        .map_err(|e| OracleError::AttestationDecodeError(format!("{:?}", e)))
 }
 
 pub mod oracle_queryable;
+pub mod oracle_scheduler;
+pub mod pricefeeds;
 pub mod secret_key;
+pub mod signer;
 pub mod vault;
+pub mod verification;
 
 pub use oracle_queryable::messaging::EventDescriptor;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::test_utils::{asset_pair_info, oracle};
+
+    #[tokio::test]
+    async fn attests_events_once() {
+        let oracle = oracle(asset_pair_info());
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let event = oracle
+            .create_event("event".to_string(), maturation, None, None, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            event.announcement,
+            oracle
+                .create_event("event".to_string(), maturation, None, None, None)
+                .await
+                .unwrap()
+                .announcement
+        );
+
+        let attested = oracle
+            .attest("event".to_string(), "42".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(Some(42), attested.outcome);
+        attested.verify(oracle.get_secp()).unwrap();
+        assert_eq!(
+            attested.attestation,
+            oracle
+                .attest("event".to_string(), "42".to_string(), None, None)
+                .await
+                .unwrap()
+                .attestation
+        );
+        assert!(matches!(
+            oracle
+                .attest("event".to_string(), "43".to_string(), None, None)
+                .await,
+            Err(OracleError::AlreadyAttestedError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_events() {
+        assert!(matches!(
+            oracle(asset_pair_info())
+                .attest("event".to_string(), "42".to_string(), None, None)
+                .await,
+            Err(OracleError::EventNotFoundError)
+        ));
+    }
+
+    #[tokio::test]
+    async fn only_overwrites_events_with_the_same_announcement_and_attestation() {
+        let other_oracle = oracle(asset_pair_info());
+        let oracle = oracle(asset_pair_info());
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let unattested = oracle
+            .create_event("event".to_string(), maturation, None, None, None)
+            .await
+            .unwrap();
+        let attested = oracle
+            .attest("event".to_string(), "42".to_string(), None, None)
+            .await
+            .unwrap();
+        let other = other_oracle
+            .create_event(
+                "event".to_string(),
+                maturation + time::Duration::days(1),
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        for conflicting in [unattested, other] {
+            assert!(matches!(
+                oracle.import_events(vec![conflicting], true).await,
+                Err(OracleError::EventConflictError(_))
+            ));
+        }
+        assert_eq!(
+            1,
+            oracle
+                .import_events(vec![attested.clone()], true)
+                .await
+                .unwrap()
+        );
+        assert_eq!(
+            Some(attested.attestation),
+            oracle
+                .get_event("event".to_string())
+                .await
+                .unwrap()
+                .map(|event| event.attestation)
+        );
+    }
+
+    #[tokio::test]
+    async fn records_clamped_outcomes() {
+        let mut asset_pair_info = asset_pair_info();
+        asset_pair_info.out_of_range_policy = OutOfRangePolicy::ClampToMax;
+        let oracle = oracle(asset_pair_info);
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        for (uuid, outcome, attested, unclamped) in
+            [("above", "300", 255, Some(300)), ("in_range", "5", 5, None)]
+        {
+            oracle
+                .create_event(uuid.to_string(), maturation, None, None, None)
+                .await
+                .unwrap();
+            let event = oracle
+                .attest(uuid.to_string(), outcome.to_string(), None, None)
+                .await
+                .unwrap();
+            assert_eq!(Some(attested), event.outcome);
+            assert_eq!(unclamped, event.unclamped_outcome);
+        }
+    }
+
+    fn signed_asset_pair_info(out_of_range_policy: OutOfRangePolicy) -> AssetPairInfo {
+        let mut asset_pair_info = asset_pair_info();
+        asset_pair_info.event_descriptor.is_signed = true;
+        asset_pair_info.event_descriptor.num_digits = 4;
+        asset_pair_info.out_of_range_policy = out_of_range_policy;
+        asset_pair_info
+    }
+
+    #[tokio::test]
+    async fn rejects_outcomes_below_the_range_of_signed_events() {
+        let oracle = oracle(signed_asset_pair_info(OutOfRangePolicy::Reject));
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        for uuid in ["min", "below"] {
+            oracle
+                .create_event(uuid.to_string(), maturation, None, None, None)
+                .await
+                .unwrap();
+        }
+        let event = oracle
+            .attest("min".to_string(), "-15".to_string(), None, None)
+            .await
+            .unwrap();
+        assert_eq!(Some(-15), event.outcome);
+        assert_eq!(
+            vec!["-", "1", "1", "1", "1"],
+            decode_attestation(&event.attestation.unwrap())
+                .unwrap()
+                .outcomes
+        );
+        assert!(matches!(
+            oracle
+                .attest("below".to_string(), "-16".to_string(), None, None)
+                .await,
+            Err(OracleError::OutcomeOutOfRangeError(-16))
+        ));
+    }
+
+    #[tokio::test]
+    async fn clamps_outcomes_of_signed_events_to_their_range() {
+        let oracle = oracle(signed_asset_pair_info(OutOfRangePolicy::ClampToMax));
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        for (uuid, outcome, attested) in [("above", "20", 15), ("below", "-20", -15)] {
+            oracle
+                .create_event(uuid.to_string(), maturation, None, None, None)
+                .await
+                .unwrap();
+            let event = oracle
+                .attest(uuid.to_string(), outcome.to_string(), None, None)
+                .await
+                .unwrap();
+            assert_eq!(Some(attested), event.outcome);
+            assert_eq!(Some(outcome.parse().unwrap()), event.unclamped_outcome);
+        }
+    }
+
+    #[tokio::test]
+    async fn returns_existing_events_without_validating_them_again() {
+        let oracle = oracle(asset_pair_info());
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let event = oracle
+            .create_event("event".to_string(), maturation, None, None, None)
+            .await
+            .unwrap();
+        let invalid = crate::EnumEventDescriptor { outcomes: vec![] }.into();
+        assert_eq!(
+            event.announcement,
+            oracle
+                .create_event("event".to_string(), maturation, Some(invalid), None, None)
+                .await
+                .unwrap()
+                .announcement
+        );
+    }
+}
@@ -3,7 +3,7 @@ use secp256k1_zkp::{
     hashes::*, schnorr::Signature as SchnorrSignature, ThirtyTwoByteHash,
     XOnlyPublicKey as SchnorrPublicKey,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use time::OffsetDateTime;
 
 const ORACLE_ANNOUNCEMENT_MIDSTATE: [u8; 32] = [
@@ -37,11 +37,21 @@ pub struct Announcement {
 pub struct OracleEvent {
     pub nonces: Vec<SchnorrPublicKey>,
     pub maturation: OffsetDateTime,
-    pub event_descriptor: EventDescriptor,
+    pub event_descriptor: OracleEventDescriptor,
     pub event_id: String,
 }
 
-#[derive(Clone, Debug, Deserialize)]
+/// The kind of outcome an event is attested with.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OracleEventDescriptor {
+    /// numeric outcome, attested digit by digit
+    DigitDecompositionEvent(EventDescriptor),
+    /// outcome from a list of possible outcomes, attested with a single nonce
+    EnumEvent(EnumEventDescriptor),
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub struct EventDescriptor {
     pub base: u16,
     pub is_signed: bool,
@@ -50,8 +60,15 @@ pub struct EventDescriptor {
     pub num_digits: u16,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct EnumEventDescriptor {
+    pub outcomes: Vec<String>,
+}
+
 #[derive(Clone, Debug)]
 pub struct Attestation {
+    /// id of the attested event, which only the Suredbits encoding carries
+    pub event_id: String,
     pub oracle_pubkey: SchnorrPublicKey,
     pub signatures: Vec<SchnorrSignature>,
     pub outcomes: Vec<String>,
@@ -94,6 +111,119 @@ impl From<&OracleEvent> for dlc_messages::oracle_msgs::OracleEvent {
     }
 }
 
+impl EventDescriptor {
+    /// Returns the largest outcome that fits in `num_digits` digits in `base`, saturating at
+    /// `i64::MAX`.
+    pub fn max_outcome(&self) -> i64 {
+        u64::from(self.base)
+            .checked_pow(self.num_digits.into())
+            .and_then(|nb_outcomes| i64::try_from(nb_outcomes - 1).ok())
+            .unwrap_or(i64::MAX)
+    }
+
+    /// Returns the smallest outcome that fits in `num_digits` digits in `base`, which is 0 for
+    /// unsigned events.
+    pub fn min_outcome(&self) -> i64 {
+        if self.is_signed {
+            -self.max_outcome()
+        } else {
+            0
+        }
+    }
+
+    /// Decomposes `outcome` into `num_digits` digits in `base`, most significant first, each
+    /// written in decimal as required by the DLC spec. Signed events are preceded by the sign of
+    /// `outcome`, `+` or `-`, attested with its own nonce. Returns `None` if `outcome` does not fit
+    /// in `num_digits` digits, or is negative and the event is unsigned.
+    pub fn decompose(&self, outcome: i64) -> Option<Vec<String>> {
+        if outcome < 0 && !self.is_signed {
+            return None;
+        }
+        let base = u64::from(self.base);
+        let mut digits = vec![0u64; self.num_digits.into()];
+        let mut remainder = outcome.unsigned_abs();
+        for digit in digits.iter_mut().rev() {
+            *digit = remainder % base;
+            remainder /= base;
+        }
+        if remainder != 0 {
+            return None;
+        }
+        let sign = self
+            .is_signed
+            .then(|| if outcome < 0 { "-" } else { "+" }.to_string());
+        Some(
+            sign.into_iter()
+                .chain(digits.iter().map(ToString::to_string))
+                .collect(),
+        )
+    }
+}
+
+impl OracleEventDescriptor {
+    /// Returns the number of nonces, and thus signatures, required by events of this kind.
+    pub fn nb_nonces(&self) -> usize {
+        match self {
+            OracleEventDescriptor::DigitDecompositionEvent(e) => {
+                usize::from(e.num_digits) + usize::from(e.is_signed)
+            }
+            OracleEventDescriptor::EnumEvent(_) => 1,
+        }
+    }
+}
+
+impl From<EventDescriptor> for OracleEventDescriptor {
+    fn from(event: EventDescriptor) -> Self {
+        Self::DigitDecompositionEvent(event)
+    }
+}
+
+impl From<EnumEventDescriptor> for OracleEventDescriptor {
+    fn from(event: EnumEventDescriptor) -> Self {
+        Self::EnumEvent(event)
+    }
+}
+
+impl From<&dlc_messages::oracle_msgs::EventDescriptor> for OracleEventDescriptor {
+    fn from(event: &dlc_messages::oracle_msgs::EventDescriptor) -> Self {
+        match event {
+            dlc_messages::oracle_msgs::EventDescriptor::DigitDecompositionEvent(e) => {
+                Self::DigitDecompositionEvent(e.into())
+            }
+            dlc_messages::oracle_msgs::EventDescriptor::EnumEvent(e) => {
+                Self::EnumEvent(EnumEventDescriptor {
+                    outcomes: e.outcomes.clone(),
+                })
+            }
+        }
+    }
+}
+
+impl From<&OracleEventDescriptor> for dlc_messages::oracle_msgs::EventDescriptor {
+    fn from(event: &OracleEventDescriptor) -> Self {
+        match event {
+            OracleEventDescriptor::DigitDecompositionEvent(e) => e.into(),
+            OracleEventDescriptor::EnumEvent(e) => {
+                Self::EnumEvent(dlc_messages::oracle_msgs::EnumEventDescriptor {
+                    outcomes: e.outcomes.clone(),
+                })
+            }
+        }
+    }
+}
+
+impl From<&dlc_messages::oracle_msgs::DigitDecompositionEventDescriptor> for EventDescriptor {
+    fn from(event: &dlc_messages::oracle_msgs::DigitDecompositionEventDescriptor) -> Self {
+        Self {
+            base: event.base,
+            is_signed: event.is_signed,
+            unit: event.unit.clone(),
+            precision: event.precision,
+            num_digits: event.nb_digits,
+        }
+    }
+}
+
 impl From<&EventDescriptor> for dlc_messages::oracle_msgs::EventDescriptor {
     fn from(event: &EventDescriptor) -> Self {
         let base = event.base;
@@ -153,15 +283,102 @@ impl Writeable for BigSize {
     }
 }
 
+// TLV types of the Suredbits (bitcoin-s) encodings
+const SUREDBITS_ANNOUNCEMENT_TYPE: u64 = 55332;
+const SUREDBITS_ATTESTATION_TYPE: u64 = 55400;
+const SUREDBITS_EVENT_TYPE: u64 = 55330;
+const SUREDBITS_ENUM_EVENT_DESCRIPTOR_TYPE: u64 = 55302;
+const SUREDBITS_DIGIT_DECOMPOSITION_EVENT_DESCRIPTOR_TYPE: u64 = 55306;
+
+fn write_tlv(out: &mut Vec<u8>, type_id: u64, value: &[u8]) {
+    BigSize(type_id).write(out).unwrap();
+    BigSize(value.len() as u64).write(out).unwrap();
+    out.extend_from_slice(value);
+}
+
+fn write_string(out: &mut Vec<u8>, string: &str) {
+    BigSize(string.len() as u64).write(out).unwrap();
+    out.extend_from_slice(string.as_bytes());
+}
+
+fn write_suredbits_event_descriptor(
+    out: &mut Vec<u8>,
+    event_descriptor: &dlc_messages::oracle_msgs::EventDescriptor,
+) {
+    let mut value = vec![];
+    match event_descriptor {
+        dlc_messages::oracle_msgs::EventDescriptor::EnumEvent(e) => {
+            value.extend_from_slice(&(e.outcomes.len() as u16).to_be_bytes());
+            for outcome in &e.outcomes {
+                write_string(&mut value, outcome);
+            }
+            write_tlv(out, SUREDBITS_ENUM_EVENT_DESCRIPTOR_TYPE, &value);
+        }
+        dlc_messages::oracle_msgs::EventDescriptor::DigitDecompositionEvent(e) => {
+            value.extend_from_slice(&e.base.to_be_bytes());
+            value.push(e.is_signed.into());
+            write_string(&mut value, &e.unit);
+            value.extend_from_slice(&e.precision.to_be_bytes());
+            value.extend_from_slice(&e.nb_digits.to_be_bytes());
+            write_tlv(
+                out,
+                SUREDBITS_DIGIT_DECOMPOSITION_EVENT_DESCRIPTOR_TYPE,
+                &value,
+            );
+        }
+    }
+}
+
+/// Encodes `announcement` as the `oracle_announcement` TLV of Suredbits' oracles, i.e. prefixed
+/// with its type and length, as served to bitcoin-s based wallets.
+pub fn suredbits_encode_announcement(
+    announcement: &dlc_messages::oracle_msgs::OracleAnnouncement,
+) -> Vec<u8> {
+    let event = &announcement.oracle_event;
+    let mut event_value = vec![];
+    event_value.extend_from_slice(&(event.oracle_nonces.len() as u16).to_be_bytes());
+    for nonce in &event.oracle_nonces {
+        event_value.extend_from_slice(&nonce.serialize());
+    }
+    event_value.extend_from_slice(&event.event_maturity_epoch.to_be_bytes());
+    write_suredbits_event_descriptor(&mut event_value, &event.event_descriptor);
+    write_string(&mut event_value, &event.event_id);
+
+    let mut value = vec![];
+    value.extend_from_slice(announcement.announcement_signature.as_ref());
+    value.extend_from_slice(&announcement.oracle_public_key.serialize());
+    write_tlv(&mut value, SUREDBITS_EVENT_TYPE, &event_value);
+
+    let mut out = vec![];
+    write_tlv(&mut out, SUREDBITS_ANNOUNCEMENT_TYPE, &value);
+    out
+}
+
+/// Encodes `attestation` of event `event_id` as the `oracle_attestation` TLV of Suredbits'
+/// oracles, which unlike the DLC spec's carries the event id and no outcome count.
+pub fn suredbits_encode_attestation(
+    event_id: &str,
+    attestation: &dlc_messages::oracle_msgs::OracleAttestation,
+) -> Vec<u8> {
+    let mut value = vec![];
+    write_string(&mut value, event_id);
+    value.extend_from_slice(&attestation.oracle_public_key.serialize());
+    value.extend_from_slice(&(attestation.signatures.len() as u16).to_be_bytes());
+    for signature in &attestation.signatures {
+        value.extend_from_slice(signature.as_ref());
+    }
+    for outcome in &attestation.outcomes {
+        write_string(&mut value, outcome);
+    }
+
+    let mut out = vec![];
+    write_tlv(&mut out, SUREDBITS_ATTESTATION_TYPE, &value);
+    out
+}
+
 impl Announcement {
     pub fn suredbits_encode(&self) -> Vec<u8> {
-        let mut out = vec![];
-        BigSize(55332_u64).write(&mut out).unwrap();
-        let v = dlc_messages::oracle_msgs::OracleAnnouncement::from(self).encode();
-        BigSize(v.len() as u64).write(&mut out).unwrap();
-        // extend manually for announcement to be consistent w suredbits decoding
-        out.extend_from_slice(&v);
-        out
+        suredbits_encode_announcement(&self.into())
     }
 
     pub fn encode(&self) -> Vec<u8> {
@@ -177,26 +394,7 @@ impl OracleEvent {
 
 impl Attestation {
     pub fn suredbits_encode(&self) -> Vec<u8> {
-        let mut out = vec![];
-        BigSize(55400_u64).write(&mut out).unwrap();
-        let mut v = dlc_messages::oracle_msgs::OracleAttestation::from(self).encode();
-
-        // println!("Original V:\n{:?}", v);
-
-        // remove duplicate length encoding from tibo's implementation
-        let end = v.len() - 36;
-        v.drain(end - 2..end);
-
-        BigSize((v.len() + 1) as u64).write(&mut out).unwrap();
-        // extend manually for attestation to be consistent w suredbits decoding
-
-        BigSize(0_u64).write(&mut out).unwrap();
-        out.extend_from_slice(&v);
-        // println!("Original Out:\n{:?}", out);
-
-        // let new_v = out.clone();
-        // println!("Back to V:\n{:?}", &new_v[7..]);
-        out
+        suredbits_encode_attestation(&self.event_id, &self.into())
     }
 
     pub fn encode(&self) -> Vec<u8> {
@@ -204,14 +402,85 @@ impl Attestation {
     }
 }
 
-// todo: update to test w James' decoding
 #[cfg(test)]
 mod tests {
     use super::*;
     use time::format_description::well_known::Rfc3339;
 
-    // does not work yet because suredbits and tibo's implementations must be unified first
-    #[ignore]
+    fn descriptor(base: u16, num_digits: u16) -> EventDescriptor {
+        EventDescriptor {
+            base,
+            is_signed: false,
+            unit: "BTCUSD".to_string(),
+            precision: 0,
+            num_digits,
+        }
+    }
+
+    fn digits(digits: &[&str]) -> Option<Vec<String>> {
+        Some(digits.iter().map(ToString::to_string).collect())
+    }
+
+    #[test]
+    fn decomposes_outcome_into_digits() {
+        assert_eq!(digits(&["0", "1", "0", "1"]), descriptor(2, 4).decompose(5));
+        assert_eq!(
+            digits(&["0", "3", "0", "2"]),
+            descriptor(10, 4).decompose(302)
+        );
+        assert_eq!(digits(&["15", "0"]), descriptor(16, 2).decompose(240));
+    }
+
+    #[test]
+    fn rejects_outcome_exceeding_digits() {
+        assert_eq!(None, descriptor(2, 4).decompose(16));
+        assert!(descriptor(2, 4).decompose(15).is_some());
+        assert_eq!(None, descriptor(10, 2).decompose(100));
+    }
+
+    #[test]
+    fn computes_outcome_range() {
+        assert_eq!(15, descriptor(2, 4).max_outcome());
+        assert_eq!(999, descriptor(10, 3).max_outcome());
+        assert_eq!(i64::MAX, descriptor(2, 64).max_outcome());
+        assert_eq!(i64::MAX, descriptor(2, 63).max_outcome());
+        assert_eq!(0, descriptor(2, 4).min_outcome());
+        let signed = EventDescriptor {
+            is_signed: true,
+            ..descriptor(2, 4)
+        };
+        assert_eq!(-15, signed.min_outcome());
+        assert!(signed.decompose(signed.min_outcome()).is_some());
+        assert!(signed.decompose(signed.max_outcome()).is_some());
+    }
+
+    #[test]
+    fn decomposes_signed_outcome() {
+        let signed = EventDescriptor {
+            is_signed: true,
+            ..descriptor(2, 4)
+        };
+        assert_eq!(digits(&["+", "0", "1", "0", "1"]), signed.decompose(5));
+        assert_eq!(digits(&["-", "0", "1", "0", "1"]), signed.decompose(-5));
+        assert_eq!(digits(&["+", "0", "0", "0", "0"]), signed.decompose(0));
+        assert_eq!(None, signed.decompose(-16));
+        assert_eq!(None, descriptor(2, 4).decompose(-1));
+        assert_eq!(5, OracleEventDescriptor::from(signed).nb_nonces());
+    }
+
+    #[test]
+    fn enum_event_descriptor_round_trips() {
+        let event_descriptor: OracleEventDescriptor = EnumEventDescriptor {
+            outcomes: vec!["yes".to_string(), "no".to_string()],
+        }
+        .into();
+        assert_eq!(1, event_descriptor.nb_nonces());
+        let dlc_event_descriptor: dlc_messages::oracle_msgs::EventDescriptor =
+            (&event_descriptor).into();
+        assert_eq!(event_descriptor, (&dlc_event_descriptor).into());
+    }
+
+    // vectors of an announcement and attestation of Suredbits' oracle
     #[test]
     fn suredbits_announcement_encodes_correctly() {
         let oracle_event = OracleEvent {
@@ -244,9 +513,10 @@ mod tests {
                 is_signed: false,
                 unit: "BTCUSD".to_string(),
                 precision: 0,
-                num_digits: 10,
-            },
-            event_id: "testeventid".to_string(),
+                num_digits: 18,
+            }
+            .into(),
+            event_id: "Deribit-BTC-11APR22".to_string(),
         };
 
         let announcement = Announcement {
@@ -261,15 +531,14 @@ mod tests {
             ::hex::decode(
                 "fdd824fd02d4e15edeeb14a6bfa3995eeb65208c6698690bb497f22446b0505981ec202bcbc8584077c9f7209a2cf7d459c6a891a3eb863a78355868760455845f1a15fd685804ba9838623f02c940d20d7b185d410178cff7990c7fcf19186c7f58c7c4b8defdd822fd026e0012bf0b6c97a9a33f7499511b68b0c1e5a758ad51df9330b4b6d4fd841af141bb915d25e99260cec3e1a74257aa1edffbb2c718091d8eecd77f7dd078c69782938e6b49f9c7b8b7aa815f2c2d646a0fffd713491a6af3506aa83b3eb7122a1a502d2a2a83f8121013eeb1131f6f6b805e79f6cc066cae938d7873a5979d2f4888ce0b6d548fa7fff606ec1d3668f4f407da02e3ed3090919a77c5efcb25874c1102ac38396a26028d6e4203b6bb684b49bf95c5a67fe126a46cdd0c5287f1c3779988059ddfddc40bd7d4bd03f59d2df492f903b45557d6382c5e3931a34ebd175e32c1cc1b591cafb4fa6065948300e485d939bf405128dcc096eca3f4d5b68d6c0d77575d4c7342025e04f4de78f188808a85ebb3791a75c34ebdcd54104f8a7386e831876500d92aefe123c511b003c5aaee355a47ee076685687ca3549d5ccb15c56b9e1ad5e300888671ba60e16fc4c2b77617982e42ab401a1f313834b7d17785aed84409206be3b0e62d8f735466007aa1d3709015506d31f497de0eaa43b8530f83ae8bf455069d4754847512de24892a789a3bc90904255ebd27ee78cdc68b2063ee08249682665644146af85f375f4967d5693cbc7473286180ad86290bd0d39fbb1846496cd7acb8bde77131fb1a2ecd8b2430bf84e61e13bd1b6d7d3cd13b98d0ebf476b1ab067135e8d70334bc72ff454b617d48d0851ba01b4b327b37e1c81bafed68ae1f951dc340ae6cd036b67212847aed94322d3397d4e2335c4e560486f6daa8c6074f258e3a348e2937ecba83fa8d80196f23a486cf0a306253e000fdd80a100002000642544355534400000000001213446572696269742d4254432d31314150523232"
             ).unwrap(),
-            announcement.encode(),
+            announcement.suredbits_encode(),
         );
     }
 
-    // does not work yet because suredbits and tibo's implementations must be unified first
-    #[ignore]
     #[test]
     fn suredbits_attestation_encodes_correctly() {
         let attestation = Attestation {
+            event_id: "Deribit-BTC-11APR22".to_string(),
             oracle_pubkey: SchnorrPublicKey::from_slice(&::hex::decode("04ba9838623f02c940d20d7b185d410178cff7990c7fcf19186c7f58c7c4b8de").unwrap()).unwrap(),
             signatures: vec![
                 "bf0b6c97a9a33f7499511b68b0c1e5a758ad51df9330b4b6d4fd841af141bb9195cad468377a389ce8b46d508b16a81ee4a43e074999777717b8208801f41772",
@@ -301,7 +570,7 @@ mod tests {
             ::hex::decode(
                 "fdd868fd04da13446572696269742d4254432d3131415052323204ba9838623f02c940d20d7b185d410178cff7990c7fcf19186c7f58c7c4b8de0012bf0b6c97a9a33f7499511b68b0c1e5a758ad51df9330b4b6d4fd841af141bb9195cad468377a389ce8b46d508b16a81ee4a43e074999777717b8208801f417725d25e99260cec3e1a74257aa1edffbb2c718091d8eecd77f7dd078c69782938e233b3c4cbcbcca11b35127d77e35cfffc10f7c09e5cb547b334e71f10fe29c356b49f9c7b8b7aa815f2c2d646a0fffd713491a6af3506aa83b3eb7122a1a502dd6945e7a18e975fba732be0f09d42553f16bee156896dca058e8aeafb9609b9b2a2a83f8121013eeb1131f6f6b805e79f6cc066cae938d7873a5979d2f4888cedbd8dafecccbfd279056c3b9e6b4cb981fd8a76ba5a0cd835bd514d357cdae5f0b6d548fa7fff606ec1d3668f4f407da02e3ed3090919a77c5efcb25874c11020ea572df9b34d6836b360a014a5523ec11eecf5611e5aa66cea4b9fede0d22fbac38396a26028d6e4203b6bb684b49bf95c5a67fe126a46cdd0c5287f1c37799d6e17cd3e395402885538c1b0f711da9b5879d977ccc7fe4b3c92f789ccae53188059ddfddc40bd7d4bd03f59d2df492f903b45557d6382c5e3931a34ebd175e0a061aef29d81c824582f5375dbc6f28edcd8428e3e0b32f31abc98de34cd2fd32c1cc1b591cafb4fa6065948300e485d939bf405128dcc096eca3f4d5b68d6c3ba1cbe15e85555143190164296075bd29c468733bfee2dc97205b6a56d2f81e0d77575d4c7342025e04f4de78f188808a85ebb3791a75c34ebdcd54104f8a735938c8fa09e89345260dc7e694d79163029285cc0b09379edf179af01a442b0786e831876500d92aefe123c511b003c5aaee355a47ee076685687ca3549d5ccb30ca20cf1544862294a87d46d72fb73cc03e483102b12770d86cc157123109bd15c56b9e1ad5e300888671ba60e16fc4c2b77617982e42ab401a1f313834b7d1afe18b838fe7a9ee6e6f5edf10eec22cdbfa8ca619ad91987a6012f472b199417785aed84409206be3b0e62d8f735466007aa1d3709015506d31f497de0eaa43e010f31e41525eda44819b5659a24d5d64e99205e7abf81015923ae12e55b085b8530f83ae8bf455069d4754847512de24892a789a3bc90904255ebd27ee78cdb5da94763dda5c30e5d218779a99ff5272237d9e00f3b19cf2870715f84dd8fec68b2063ee08249682665644146af85f375f4967d5693cbc7473286180ad8629efa8cb6bc07194b0b1bead68891c95e80977eb9e0e6d4a2c88f8dbd1629df6d70bd0d39fbb1846496cd7acb8bde77131fb1a2ecd8b2430bf84e61e13bd1b6d7d4b22c794c83623922ae658d94ea2910af92b858f57222038dbe788cba48e202e3cd13b98d0ebf476b1ab067135e8d70334bc72ff454b617d48d0851ba01b4b32415ae5a55f8f2a594dcfd92d8a737a0f8c49f05d2040d29cd26d06075884d2317b37e1c81bafed68ae1f951dc340ae6cd036b67212847aed94322d3397d4e233fe6988151ded6eef0a57cc0e76a80d54c61ccedf179ebfb41f4fe9f2355fb3d85c4e560486f6daa8c6074f258e3a348e2937ecba83fa8d80196f23a486cf0a30e8bc2a1eeb140c04e132c02ef69a81e88e73582a8be4b788ffd0aef4a1113dad013001300131013001310130013001310130013101300131013001300131013101310130"
             ).unwrap(),
-            attestation.encode(),
+            attestation.suredbits_encode(),
         );
     }
 }
@@ -0,0 +1,13 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, OracleSchedulerError>;
+
+#[derive(Debug, Display, Error)]
+pub enum OracleSchedulerError {
+    /// oracle error: {0}
+    OracleError(#[from] crate::oracle::OracleError),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+}
@@ -0,0 +1,476 @@
+use crate::{
+    oracle::{
+        decode_announcement,
+        pricefeeds::PriceFeed,
+        signer::{Signer, SignerError, SigningRequest},
+        Oracle, OracleError, StoredEvent,
+    },
+    Announcement, AssetPair, Attestation, OracleEvent, OracleEventDescriptor,
+};
+use clokwerk::{AsyncScheduler, TimeUnits};
+use log::{error, info, warn};
+use secp256k1_zkp::{
+    hashes::*, All, KeyPair, Secp256k1, SecretKey, XOnlyPublicKey as SchnorrPublicKey,
+};
+use std::{collections::VecDeque, sync::Arc};
+use time::{Duration, OffsetDateTime};
+use tokio::sync::Mutex;
+
+mod error;
+pub use error::OracleSchedulerError;
+pub use error::Result;
+
+// how often the scheduler checks for announcements and attestations that are due
+const SCHEDULER_TICK_SECONDS: u32 = 60;
+const SCHEDULER_SLEEP_TIME: std::time::Duration = std::time::Duration::from_millis(100);
+// how long after maturation events may still be attested with the current price, when the
+// pricefeeds cannot report the price at maturation
+const MAX_ATTESTATION_DELAY: Duration = Duration::minutes(5);
+
+// tag of the hash nonces are derived with, see `derive_sk_nonces`
+const NONCE_TAG: &[u8] = b"sibyls/nonce";
+
+/// Derives the secret nonces of the event `event_id` of `asset_pair`, one per digit, from the
+/// oracle secret key.
+///
+/// Nonce `i` is the tagged hash `SHA256(SHA256(tag) || SHA256(tag) || sk || len(asset_pair) ||
+/// asset_pair || event_id || i)` with tag `sibyls/nonce`, lengths and `i` being big endian `u32`s.
+/// Nonces can thus be recomputed when attesting instead of being stored, and are distinct across
+/// events as long as event ids are unique per asset pair.
+pub fn derive_sk_nonces(
+    keypair: &KeyPair,
+    asset_pair: &AssetPair,
+    event_id: &str,
+    nb_nonces: usize,
+) -> Vec<[u8; 32]> {
+    let tag_hash = sha256::Hash::hash(NONCE_TAG);
+    let secret_key = SecretKey::from_keypair(keypair).secret_bytes();
+    (0..nb_nonces as u32)
+        .map(|index| {
+            let mut engine = sha256::Hash::engine();
+            engine.input(&tag_hash[..]);
+            engine.input(&tag_hash[..]);
+            engine.input(&secret_key);
+            engine.input(&(asset_pair.as_str().len() as u32).to_be_bytes());
+            engine.input(asset_pair.as_str().as_bytes());
+            engine.input(event_id.as_bytes());
+            engine.input(&index.to_be_bytes());
+            sha256::Hash::from_engine(engine).into_inner()
+        })
+        .collect()
+}
+
+/// Returns the public nonces of `sk_nonces`.
+pub fn nonces_of(
+    secp: &Secp256k1<All>,
+    sk_nonces: &[[u8; 32]],
+) -> std::result::Result<Vec<SchnorrPublicKey>, secp256k1_zkp::UpstreamError> {
+    sk_nonces
+        .iter()
+        .map(|sk_nonce| {
+            let oracle_r_kp = secp256k1_zkp::KeyPair::from_seckey_slice(secp, sk_nonce)?;
+            Ok(SchnorrPublicKey::from_keypair(&oracle_r_kp))
+        })
+        .collect()
+}
+
+/// Builds the announcement of the event `event_id` of `asset_pair`, with the nonces `signer`
+/// derives for it and signed by its active key.
+pub fn build_announcement(
+    signer: &dyn Signer,
+    asset_pair: &AssetPair,
+    maturation: OffsetDateTime,
+    event_id: String,
+    event_descriptor: OracleEventDescriptor,
+) -> std::result::Result<Announcement, SignerError> {
+    let nonces = signer.nonces(asset_pair, &event_id, event_descriptor.nb_nonces())?;
+
+    let oracle_event = OracleEvent {
+        nonces,
+        maturation,
+        event_descriptor,
+        event_id,
+    };
+
+    Ok(Announcement {
+        signature: signer.sign_announcement(asset_pair, &oracle_event)?,
+        oracle_pubkey: signer.public_key(),
+        oracle_event,
+    })
+}
+
+/// Builds the attestation of the outcomes of `request`, signed by `signer` with the key which
+/// announced the event.
+pub fn build_attestation(
+    signer: &dyn Signer,
+    request: &SigningRequest,
+) -> std::result::Result<Attestation, SignerError> {
+    let announcement = decode_announcement(&request.announcement)
+        .map_err(|e| SignerError::InvalidAnnouncementError(e.to_string()))?;
+    let oracle_pubkey = SchnorrPublicKey::from_slice(&announcement.oracle_public_key.serialize())?;
+    Ok(Attestation {
+        event_id: announcement.oracle_event.event_id,
+        oracle_pubkey,
+        signatures: signer.sign_outcomes(request)?,
+        outcomes: request.outcomes.clone(),
+    })
+}
+
+/// Returns the id of the scheduled event of `asset_pair` maturing at `maturation`, e.g. `btcusd1653984000`.
+pub fn scheduled_event_id(asset_pair: &AssetPair, maturation: OffsetDateTime) -> String {
+    format!(
+        "{}{}",
+        asset_pair.to_string().to_lowercase(),
+        maturation.unix_timestamp()
+    )
+}
+
+fn is_scheduled_event(event: &StoredEvent) -> bool {
+    event.uuid == scheduled_event_id(&event.asset_pair, event.maturation)
+}
+
+struct OracleScheduler {
+    oracle: Oracle,
+    // announced but unattested scheduled events, ordered by maturation
+    pending_attestations: VecDeque<(String, OffsetDateTime)>,
+}
+
+impl OracleScheduler {
+    /// Maturations of the series are `attestation_time` on the unix epoch plus a whole number of
+    /// `frequency`; this returns those which should already be announced at `now` but are not due
+    /// yet.
+    fn announceable_maturations(&self, now: OffsetDateTime) -> Vec<OffsetDateTime> {
+        let config = &self.oracle.oracle_config;
+        let anchor = OffsetDateTime::UNIX_EPOCH.replace_time(config.attestation_time);
+        let frequency = config.frequency.whole_seconds();
+        let elapsed = (now - anchor).whole_seconds();
+        let first = elapsed.div_euclid(frequency) + 1;
+        let last = (elapsed + config.announcement_offset.whole_seconds()).div_euclid(frequency);
+        (first..=last)
+            .map(|slot| anchor + Duration::seconds(slot * frequency))
+            .collect()
+    }
+
+    async fn load_pending_attestations(&mut self) -> Result<()> {
+        let asset_pair = &self.oracle.asset_pair_info.asset_pair;
+        let mut pending = vec![];
+        for event in self.oracle.get_events().await? {
+            if is_scheduled_event(&event) && event.attestation.is_none() {
+                pending.push((event.uuid, event.maturation));
+            }
+        }
+        pending.sort_by_key(|(_, maturation)| *maturation);
+        info!(
+            "found {} unattested scheduled events for {}",
+            pending.len(),
+            asset_pair
+        );
+        self.pending_attestations = pending.into();
+        Ok(())
+    }
+
+    async fn announce(&mut self, now: OffsetDateTime) -> Result<()> {
+        let asset_pair = self.oracle.asset_pair_info.asset_pair.clone();
+        for maturation in self.announceable_maturations(now) {
+            let event_id = scheduled_event_id(&asset_pair, maturation);
+            if self
+                .oracle
+                .event_handler
+                .get(event_id.clone())
+                .await?
+                .is_some()
+            {
+                continue;
+            }
+            info!(
+                "creating scheduled event {} maturing at {}",
+                event_id, maturation
+            );
+            self.oracle
+                .create_event(event_id.clone(), maturation, None, None, None)
+                .await?;
+            self.pending_attestations.push_back((event_id, maturation));
+        }
+        Ok(())
+    }
+
+    /// Attests every queued event which matured at `now`. Events failing for a reason which
+    /// could go away, e.g. unavailable pricefeeds, stay queued to be retried on the next tick,
+    /// without holding up the events after them; the others are left for manual attestation, as
+    /// are events past `MAX_ATTESTATION_DELAY` unless the pricefeeds are historical.
+    async fn attest(&mut self, now: OffsetDateTime) {
+        let mut retried = vec![];
+        while let Some((event_id, maturation)) = self.pending_attestations.front().cloned() {
+            if maturation > now {
+                break;
+            }
+            self.pending_attestations.pop_front();
+            // the current price is not the price at maturation, e.g. after the oracle was down
+            if now - maturation > MAX_ATTESTATION_DELAY && !self.oracle.pricefeed.is_historical() {
+                error!(
+                    "scheduled event {} matured {} ago and the pricefeeds only report current prices, leaving it for manual attestation",
+                    event_id,
+                    now - maturation
+                );
+                continue;
+            }
+            info!("attesting scheduled event {}", event_id);
+            match self
+                .oracle
+                .attest_with_pricefeeds(event_id.clone(), None)
+                .await
+            {
+                Ok(_) => {}
+                // attested by other means in the meantime, which is final
+                Err(OracleError::AlreadyAttestedError(_)) => {
+                    warn!(
+                        "scheduled event {} was already attested with another outcome",
+                        event_id
+                    );
+                }
+                Err(err) if is_permanent_failure(&err) => {
+                    error!(
+                        "scheduled event {} cannot be attested, leaving it for manual attestation: {}",
+                        event_id, err
+                    );
+                }
+                Err(err) => {
+                    warn!(
+                        "attesting scheduled event {} failed, retrying on the next tick: {}",
+                        event_id, err
+                    );
+                    retried.push((event_id, maturation));
+                }
+            }
+        }
+        // retried events matured before the events still queued
+        for pending in retried.into_iter().rev() {
+            self.pending_attestations.push_front(pending);
+        }
+    }
+
+    /// Announces and attests the events due at `now`, a failure to announce not preventing
+    /// attestations.
+    async fn tick(&mut self, now: OffsetDateTime) {
+        if let Err(err) = self.announce(now).await {
+            error!(
+                "announcing scheduled events of {} failed: {}",
+                self.oracle.asset_pair_info.asset_pair, err
+            );
+        }
+        self.attest(now).await;
+    }
+}
+
+// whether attesting an event failed for a reason which retrying cannot fix
+fn is_permanent_failure(err: &OracleError) -> bool {
+    match err {
+        OracleError::EventNotFoundError
+        | OracleError::UnexpectedEventDescriptorError
+        | OracleError::InvalidOutcomeError(_)
+        | OracleError::OutcomeOutOfRangeError(_)
+        | OracleError::UnknownOracleKeyError(_)
+        | OracleError::NonceMismatchError(_)
+        | OracleError::InconsistentEventError(..) => true,
+        // the signer may be unreachable for a while, or the event not mature for it yet
+        OracleError::SignerError(err) => !matches!(
+            err,
+            SignerError::IoError(_)
+                | SignerError::RemoteError(_)
+                | SignerError::PrematureAttestationError(..)
+        ),
+        _ => false,
+    }
+}
+
+/// Starts the announcement and attestation schedule of `oracle` as configured by its
+/// `OracleConfig`.
+///
+/// Scheduled events missed while the oracle was down are caught up on before this returns:
+/// every unattested event whose maturation has passed is attested, if recently enough for the
+/// pricefeeds to report its price, and every event which is due to be announced is created.
+/// Asset pairs without pricefeeds have no schedule, as their events could not be attested.
+pub async fn init(oracle: Oracle) -> Result<()> {
+    let asset_pair = oracle.asset_pair_info.asset_pair.clone();
+    // scheduled events are attested from the pricefeeds, without which they would never be
+    if oracle.pricefeed.is_empty() {
+        info!(
+            "no pricefeeds configured for {}, not scheduling events",
+            asset_pair
+        );
+        return Ok(());
+    }
+    let mut oracle_scheduler = OracleScheduler {
+        oracle,
+        pending_attestations: VecDeque::new(),
+    };
+    oracle_scheduler.load_pending_attestations().await?;
+    oracle_scheduler.tick(OffsetDateTime::now_utc()).await;
+
+    info!("starting scheduler for {}", asset_pair);
+    let oracle_scheduler = Arc::new(Mutex::new(oracle_scheduler));
+    let mut scheduler = AsyncScheduler::with_tz(chrono::Utc);
+    scheduler
+        .every(SCHEDULER_TICK_SECONDS.seconds())
+        .run(move || {
+            let oracle_scheduler = oracle_scheduler.clone();
+            async move {
+                oracle_scheduler
+                    .lock()
+                    .await
+                    .tick(OffsetDateTime::now_utc())
+                    .await
+            }
+        });
+
+    tokio::spawn(async move {
+        loop {
+            scheduler.run_pending().await;
+            tokio::time::sleep(SCHEDULER_SLEEP_TIME).await;
+        }
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::test_utils::{asset_pair_info, keypair, local_signer, oracle};
+
+    #[test]
+    fn derives_deterministic_nonces() {
+        let secp = Secp256k1::new();
+        let btcusd = "BTCUSD".parse().unwrap();
+        let sk_nonces = derive_sk_nonces(&keypair(&secp), &btcusd, "event", 3);
+        assert_eq!(
+            sk_nonces,
+            derive_sk_nonces(&keypair(&secp), &btcusd, "event", 3)
+        );
+        assert_ne!(sk_nonces[0], sk_nonces[1]);
+        assert_ne!(sk_nonces[1], sk_nonces[2]);
+    }
+
+    #[test]
+    fn derives_distinct_nonces_across_events() {
+        let secp = Secp256k1::new();
+        let btcusd = "BTCUSD".parse().unwrap();
+        let sk_nonce = derive_sk_nonces(&keypair(&secp), &btcusd, "event", 1);
+        assert_ne!(
+            sk_nonce,
+            derive_sk_nonces(&keypair(&secp), &btcusd, "other", 1)
+        );
+        assert_ne!(
+            sk_nonce,
+            derive_sk_nonces(&keypair(&secp), &"ETHUSD".parse().unwrap(), "event", 1)
+        );
+        let other_keypair = KeyPair::from_seckey_slice(&secp, &[2u8; 32]).unwrap();
+        assert_ne!(
+            sk_nonce,
+            derive_sk_nonces(&other_keypair, &btcusd, "event", 1)
+        );
+    }
+
+    fn announcement() -> Announcement {
+        build_announcement(
+            &local_signer(),
+            &"BTCUSD".parse().unwrap(),
+            OffsetDateTime::UNIX_EPOCH,
+            "event".to_string(),
+            crate::EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }
+            .into(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn announces_derived_nonces() {
+        let secp = Secp256k1::new();
+        let btcusd = "BTCUSD".parse().unwrap();
+        let sk_nonces = derive_sk_nonces(&keypair(&secp), &btcusd, "event", 1);
+        assert_eq!(
+            announcement().oracle_event.nonces,
+            nonces_of(&secp, &sk_nonces).unwrap()
+        );
+    }
+
+    #[test]
+    fn attests_with_announced_nonces() {
+        use crate::oracle::verification::verify_attestation;
+
+        let secp = Secp256k1::new();
+        let btcusd: AssetPair = "BTCUSD".parse().unwrap();
+        let announcement = announcement();
+        let mut request = SigningRequest {
+            asset_pair: btcusd.clone(),
+            announcement: announcement.encode(),
+            outcomes: vec!["no".to_string()],
+            early_attestation_reason: None,
+            sk_nonces: None,
+        };
+        let attestation = build_attestation(&local_signer(), &request).unwrap();
+        verify_attestation(&secp, &(&announcement).into(), &(&attestation).into()).unwrap();
+
+        request.sk_nonces = Some(derive_sk_nonces(&keypair(&secp), &btcusd, "other", 1));
+        assert!(matches!(
+            build_attestation(&local_signer(), &request),
+            Err(SignerError::NonceMismatchError(_))
+        ));
+    }
+
+    fn scheduler(pricefeed: serde_json::Value) -> OracleScheduler {
+        let mut asset_pair_info = asset_pair_info();
+        asset_pair_info.pricefeeds = vec![serde_json::from_value(pricefeed).unwrap()];
+        OracleScheduler {
+            oracle: oracle(asset_pair_info),
+            pending_attestations: VecDeque::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn attests_events_queued_after_failing_ones() {
+        let mut scheduler = scheduler(serde_json::json!({ "type": "mock", "price": 5.0 }));
+        let now = OffsetDateTime::now_utc();
+        let maturation = now - Duration::MINUTE;
+        let event_id = scheduled_event_id(&"BTCUSD".parse().unwrap(), maturation);
+        scheduler
+            .oracle
+            .create_event(event_id.clone(), maturation, None, None, None)
+            .await
+            .unwrap();
+        scheduler.pending_attestations = vec![
+            ("deleted".to_string(), maturation - Duration::SECOND),
+            (event_id.clone(), maturation),
+        ]
+        .into();
+
+        scheduler.attest(now).await;
+        assert!(scheduler.pending_attestations.is_empty());
+        let event = scheduler.oracle.get_event(event_id).await.unwrap().unwrap();
+        assert_eq!(Some(5), event.outcome);
+    }
+
+    #[tokio::test]
+    async fn leaves_late_events_to_current_pricefeeds() {
+        let mut scheduler = scheduler(serde_json::json!({
+            "type": "file",
+            "path": "price.json",
+            "pointer": "/price",
+        }));
+        let now = OffsetDateTime::now_utc();
+        let maturation = now - Duration::HOUR;
+        let event_id = scheduled_event_id(&"BTCUSD".parse().unwrap(), maturation);
+        scheduler
+            .oracle
+            .create_event(event_id.clone(), maturation, None, None, None)
+            .await
+            .unwrap();
+        scheduler.pending_attestations = vec![(event_id.clone(), maturation)].into();
+
+        scheduler.attest(now).await;
+        assert!(scheduler.pending_attestations.is_empty());
+        let event = scheduler.oracle.get_event(event_id).await.unwrap().unwrap();
+        assert!(event.attestation.is_none());
+    }
+}
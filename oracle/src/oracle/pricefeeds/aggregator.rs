@@ -0,0 +1,240 @@
+use super::{PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use futures::future::join_all;
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// How the prices of several pricefeeds are combined into one.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct PriceAggregationConfig {
+    /// minimum number of prices remaining after outlier rejection
+    pub min_sources: usize,
+    /// maximum relative deviation from the median, e.g. 0.05 for 5%, above which a price is rejected
+    pub max_deviation: f64,
+}
+
+impl Default for PriceAggregationConfig {
+    fn default() -> Self {
+        PriceAggregationConfig {
+            min_sources: 1,
+            max_deviation: 0.05,
+        }
+    }
+}
+
+/// A pricefeed reporting the median of several pricefeeds, after rejecting the prices which
+/// deviate too much from the median of all of them.
+pub struct PriceAggregator {
+    pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+    config: PriceAggregationConfig,
+}
+
+impl PriceAggregator {
+    pub fn new(
+        pricefeeds: Vec<Box<dyn PriceFeed + Send + Sync>>,
+        config: PriceAggregationConfig,
+    ) -> Self {
+        Self { pricefeeds, config }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.pricefeeds.is_empty()
+    }
+}
+
+fn median(sorted_prices: &[f64]) -> f64 {
+    let mid = sorted_prices.len() / 2;
+    if sorted_prices.len() % 2 == 0 {
+        (sorted_prices[mid - 1] + sorted_prices[mid]) / 2.0
+    } else {
+        sorted_prices[mid]
+    }
+}
+
+fn aggregate(mut prices: Vec<f64>, config: &PriceAggregationConfig) -> Result<f64> {
+    let min_sources = config.min_sources.max(1);
+    if prices.len() < min_sources {
+        return Err(PriceFeedError::InsufficientPricesError(
+            prices.len(),
+            min_sources,
+        ));
+    }
+    prices.sort_by(|a, b| a.total_cmp(b));
+    let all_median = median(&prices);
+    let accepted = prices
+        .iter()
+        .copied()
+        .filter(|price| (price - all_median).abs() <= all_median.abs() * config.max_deviation)
+        .collect::<Vec<_>>();
+    if accepted.len() < prices.len() {
+        warn!(
+            "rejected {} of {} prices deviating more than {} from median {}",
+            prices.len() - accepted.len(),
+            prices.len(),
+            config.max_deviation,
+            all_median
+        );
+    }
+    if accepted.len() < min_sources {
+        return Err(PriceFeedError::InsufficientPricesError(
+            accepted.len(),
+            min_sources,
+        ));
+    }
+    Ok(median(&accepted))
+}
+
+#[async_trait]
+impl PriceFeed for PriceAggregator {
+    fn name(&self) -> String {
+        format!(
+            "median of [{}]",
+            self.pricefeeds
+                .iter()
+                .map(|pricefeed| pricefeed.name())
+                .collect::<Vec<_>>()
+                .join(", ")
+        )
+    }
+
+    /// Only historical if all of its pricefeeds are, as it would otherwise mix in current prices.
+    fn is_historical(&self) -> bool {
+        !self.pricefeeds.is_empty()
+            && self
+                .pricefeeds
+                .iter()
+                .all(|pricefeed| pricefeed.is_historical())
+    }
+
+    async fn retrieve_price(
+        &self,
+        asset_pair: &AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<f64> {
+        if self.pricefeeds.is_empty() {
+            return Err(PriceFeedError::NoPriceFeedsError(asset_pair.clone()));
+        }
+        info!("retrieving pricefeeds for {} at {}", asset_pair, datetime);
+        let results = join_all(
+            self.pricefeeds
+                .iter()
+                .map(|pricefeed| pricefeed.retrieve_price(asset_pair, datetime)),
+        )
+        .await;
+        let prices = results
+            .into_iter()
+            .zip(self.pricefeeds.iter())
+            .filter_map(|(result, pricefeed)| match result {
+                Ok(price) => Some(price),
+                Err(err) => {
+                    warn!("pricefeed {} failed: {}", pricefeed.name(), err);
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+        aggregate(prices, &self.config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::pricefeeds::MockPriceFeed;
+
+    fn aggregator(
+        pricefeeds: Vec<MockPriceFeed>,
+        min_sources: usize,
+        max_deviation: f64,
+    ) -> PriceAggregator {
+        PriceAggregator::new(
+            pricefeeds
+                .into_iter()
+                .map(|pricefeed| Box::new(pricefeed) as Box<dyn PriceFeed + Send + Sync>)
+                .collect(),
+            PriceAggregationConfig {
+                min_sources,
+                max_deviation,
+            },
+        )
+    }
+
+    async fn retrieve(aggregator: &PriceAggregator) -> Result<f64> {
+        aggregator
+            .retrieve_price(&"BTCUSD".parse().unwrap(), OffsetDateTime::UNIX_EPOCH)
+            .await
+    }
+
+    #[tokio::test]
+    async fn takes_median_of_prices() {
+        let odd = aggregator(
+            vec![
+                MockPriceFeed::new(30100.0),
+                MockPriceFeed::new(30000.0),
+                MockPriceFeed::new(30200.0),
+            ],
+            1,
+            0.05,
+        );
+        assert_eq!(30100.0, retrieve(&odd).await.unwrap());
+
+        let even = aggregator(
+            vec![MockPriceFeed::new(30000.0), MockPriceFeed::new(30100.0)],
+            1,
+            0.05,
+        );
+        assert_eq!(30050.0, retrieve(&even).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn rejects_outliers() {
+        let aggregator = aggregator(
+            vec![
+                MockPriceFeed::new(30000.0),
+                MockPriceFeed::new(30100.0),
+                MockPriceFeed::new(30200.0),
+                MockPriceFeed::new(90000.0),
+            ],
+            3,
+            0.05,
+        );
+        assert_eq!(30100.0, retrieve(&aggregator).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn ignores_unavailable_pricefeeds() {
+        let aggregator = aggregator(
+            vec![MockPriceFeed::unavailable(), MockPriceFeed::new(30000.0)],
+            1,
+            0.05,
+        );
+        assert_eq!(30000.0, retrieve(&aggregator).await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn requires_min_sources() {
+        let aggregator = aggregator(
+            vec![
+                MockPriceFeed::new(30000.0),
+                MockPriceFeed::new(40000.0),
+                MockPriceFeed::unavailable(),
+            ],
+            2,
+            0.05,
+        );
+        assert!(matches!(
+            retrieve(&aggregator).await,
+            Err(PriceFeedError::InsufficientPricesError(0, 2))
+        ));
+    }
+
+    #[tokio::test]
+    async fn requires_pricefeeds() {
+        assert!(matches!(
+            retrieve(&aggregator(vec![], 1, 0.05)).await,
+            Err(PriceFeedError::NoPriceFeedsError(asset_pair)) if asset_pair.as_str() == "BTCUSD"
+        ));
+    }
+}
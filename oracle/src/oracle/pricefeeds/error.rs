@@ -0,0 +1,30 @@
+use crate::AssetPair;
+use displaydoc::Display;
+use thiserror::Error;
+use time::OffsetDateTime;
+
+pub type Result<T> = std::result::Result<T, PriceFeedError>;
+
+#[derive(Debug, Display, Error)]
+pub enum PriceFeedError {
+    /// internal error: {0}
+    InternalError(String),
+
+    /// price not available for {0} at {1}
+    PriceNotAvailableError(AssetPair, OffsetDateTime),
+
+    /// no pricefeeds configured for {0}
+    NoPriceFeedsError(AssetPair),
+
+    /// only {0} of the {1} required prices are available after rejecting outliers
+    InsufficientPricesError(usize, usize),
+
+    /// http error: {0}
+    HttpError(#[from] reqwest::Error),
+
+    /// io error: {0}
+    IoError(#[from] std::io::Error),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+}
@@ -0,0 +1,147 @@
+use super::{PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use log::debug;
+use serde_json::Value;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+const HTTP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Where a [`JsonPriceFeed`] reads its JSON document from.
+#[derive(Clone, Debug)]
+pub enum JsonSource {
+    Http(String),
+    File(PathBuf),
+}
+
+/// A pricefeed reading the price from a JSON document, served over HTTP or read from a file.
+///
+/// The price is located in the document with a JSON pointer (RFC 6901), e.g. `/data/amount`, and
+/// may be either a JSON number or a string holding a number. The url, path and pointer may contain
+/// the placeholders `{asset_pair}` and `{timestamp}` (unix seconds), which are substituted with the
+/// requested asset pair and time. The document is only taken to hold the price at the requested
+/// time when the feed is `historical`, which the placeholders alone do not tell.
+#[derive(Clone, Debug)]
+pub struct JsonPriceFeed {
+    source: JsonSource,
+    pointer: String,
+    historical: bool,
+    client: reqwest::Client,
+}
+
+impl JsonPriceFeed {
+    pub fn new(source: JsonSource, pointer: String, historical: bool) -> Result<Self> {
+        let client = reqwest::Client::builder().timeout(HTTP_TIMEOUT).build()?;
+        Ok(Self {
+            source,
+            pointer,
+            historical,
+            client,
+        })
+    }
+
+    async fn retrieve_document(
+        &self,
+        asset_pair: &AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<String> {
+        match &self.source {
+            JsonSource::Http(url) => {
+                let url = substitute(url, asset_pair, datetime);
+                debug!("retrieving price document from {}", url);
+                Ok(self
+                    .client
+                    .get(url)
+                    .send()
+                    .await?
+                    .error_for_status()?
+                    .text()
+                    .await?)
+            }
+            JsonSource::File(path) => {
+                let path = substitute(&path.to_string_lossy(), asset_pair, datetime);
+                debug!("reading price document from {}", path);
+                Ok(tokio::fs::read_to_string(path).await?)
+            }
+        }
+    }
+}
+
+fn substitute(template: &str, asset_pair: &AssetPair, datetime: OffsetDateTime) -> String {
+    template
+        .replace("{asset_pair}", &asset_pair.to_string())
+        .replace("{timestamp}", &datetime.unix_timestamp().to_string())
+}
+
+fn extract_price(document: &str, pointer: &str) -> Option<f64> {
+    let document: Value = serde_json::from_str(document).ok()?;
+    let price = match document.pointer(pointer)? {
+        Value::Number(price) => price.as_f64(),
+        Value::String(price) => price.trim().parse().ok(),
+        _ => None,
+    };
+    price.filter(|price: &f64| price.is_finite())
+}
+
+#[async_trait]
+impl PriceFeed for JsonPriceFeed {
+    fn name(&self) -> String {
+        match &self.source {
+            JsonSource::Http(url) => url.clone(),
+            JsonSource::File(path) => path.to_string_lossy().to_string(),
+        }
+    }
+
+    fn is_historical(&self) -> bool {
+        self.historical
+    }
+
+    async fn retrieve_price(
+        &self,
+        asset_pair: &AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<f64> {
+        let document = self.retrieve_document(asset_pair, datetime).await?;
+        let pointer = substitute(&self.pointer, asset_pair, datetime);
+        extract_price(&document, &pointer).ok_or(PriceFeedError::PriceNotAvailableError(
+            asset_pair.clone(),
+            datetime,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extracts_numeric_and_string_prices() {
+        let document = r#"{"data":{"amount":"30236.12","base":"BTC"},"price":30236.5}"#;
+        assert_eq!(Some(30236.12), extract_price(document, "/data/amount"));
+        assert_eq!(Some(30236.5), extract_price(document, "/price"));
+        assert_eq!(None, extract_price(document, "/data/base"));
+        assert_eq!(None, extract_price(document, "/missing"));
+        assert_eq!(None, extract_price("not json", "/price"));
+    }
+
+    #[tokio::test]
+    async fn reads_price_from_file() {
+        let path = std::env::temp_dir().join("sibyls_json_pricefeed_test.json");
+        std::fs::write(&path, r#"{"BTCUSD":{"last":30236.5}}"#).unwrap();
+
+        let pricefeed = JsonPriceFeed::new(
+            JsonSource::File(path.clone()),
+            "/{asset_pair}/last".to_string(),
+            false,
+        )
+        .unwrap();
+        let price = pricefeed
+            .retrieve_price(&"BTCUSD".parse().unwrap(), OffsetDateTime::now_utc())
+            .await
+            .unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(30236.5, price);
+    }
+}
@@ -0,0 +1,44 @@
+use super::{PriceFeed, PriceFeedError, Result};
+use crate::AssetPair;
+use async_trait::async_trait;
+use time::OffsetDateTime;
+
+/// A deterministic pricefeed which always reports the same price, or never reports any.
+#[derive(Clone, Debug)]
+pub struct MockPriceFeed {
+    price: Option<f64>,
+}
+
+impl MockPriceFeed {
+    pub fn new(price: f64) -> Self {
+        Self { price: Some(price) }
+    }
+
+    /// A pricefeed for which no price is ever available.
+    pub fn unavailable() -> Self {
+        Self { price: None }
+    }
+}
+
+#[async_trait]
+impl PriceFeed for MockPriceFeed {
+    fn name(&self) -> String {
+        "mock".to_string()
+    }
+
+    /// The price being constant, it is that at any time.
+    fn is_historical(&self) -> bool {
+        true
+    }
+
+    async fn retrieve_price(
+        &self,
+        asset_pair: &AssetPair,
+        datetime: OffsetDateTime,
+    ) -> Result<f64> {
+        self.price.ok_or(PriceFeedError::PriceNotAvailableError(
+            asset_pair.clone(),
+            datetime,
+        ))
+    }
+}
@@ -0,0 +1,81 @@
+use crate::AssetPair;
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::path::PathBuf;
+use time::OffsetDateTime;
+
+mod aggregator;
+mod error;
+mod json;
+mod mock;
+pub use aggregator::{PriceAggregationConfig, PriceAggregator};
+pub use error::PriceFeedError;
+pub use error::Result;
+pub use json::{JsonPriceFeed, JsonSource};
+pub use mock::MockPriceFeed;
+
+/// A source of prices that the oracle can attest to.
+#[async_trait]
+pub trait PriceFeed {
+    /// Returns a human readable name of this feed, used for logging.
+    fn name(&self) -> String;
+
+    /// Returns whether this feed reports the price at any requested time, rather than the current
+    /// price, so that events can be attested from it well after their maturation.
+    fn is_historical(&self) -> bool {
+        false
+    }
+
+    /// Retrieves the price of `asset_pair` at `datetime`.
+    async fn retrieve_price(&self, asset_pair: &AssetPair, datetime: OffsetDateTime)
+        -> Result<f64>;
+}
+
+/// Configuration of a pricefeed in `config/asset_pair.json`.
+#[derive(Clone, Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PriceFeedConfig {
+    /// JSON document served over HTTP, see [`JsonPriceFeed`]
+    Http {
+        url: String,
+        pointer: String,
+        #[serde(default)]
+        historical: bool,
+    },
+    /// JSON document read from a file, see [`JsonPriceFeed`]
+    File {
+        path: PathBuf,
+        pointer: String,
+        #[serde(default)]
+        historical: bool,
+    },
+    /// constant price, see [`MockPriceFeed`]
+    Mock { price: Option<f64> },
+}
+
+impl PriceFeedConfig {
+    pub fn build(&self) -> Result<Box<dyn PriceFeed + Send + Sync>> {
+        Ok(match self {
+            PriceFeedConfig::Http {
+                url,
+                pointer,
+                historical,
+            } => Box::new(JsonPriceFeed::new(
+                JsonSource::Http(url.clone()),
+                pointer.clone(),
+                *historical,
+            )?),
+            PriceFeedConfig::File {
+                path,
+                pointer,
+                historical,
+            } => Box::new(JsonPriceFeed::new(
+                JsonSource::File(path.clone()),
+                pointer.clone(),
+                *historical,
+            )?),
+            PriceFeedConfig::Mock { price: Some(price) } => Box::new(MockPriceFeed::new(*price)),
+            PriceFeedConfig::Mock { price: None } => Box::new(MockPriceFeed::unavailable()),
+        })
+    }
+}
@@ -1,4 +1,8 @@
-use crate::oracle::vault::{get_secret_key, set_secret_key, OraclePrivateKey};
+use crate::oracle::keyring::{Keyring, RetiredKey};
+use crate::oracle::vault::{
+    get_retired_keys, get_secret_key, set_retired_keys, set_secret_key, OraclePrivateKey,
+    RetiredOracleKeys,
+};
 use gethostname::gethostname;
 use log::info;
 use log::warn;
@@ -6,10 +10,14 @@ use secp256k1_zkp::{rand, All, KeyPair, Secp256k1, SecretKey};
 use std::env;
 use std::fs::File;
 use std::io::prelude::*;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use time::OffsetDateTime;
 use vaultrs::error::ClientError;
 
+const SECRET_MOUNT: &str = "secret";
+const DEFAULT_SECRET_KEY_FILE: &str = "config/secret.key";
+
 /// This function returns a `KeyPair` for the given `Secp256k1` context.
 ///
 /// It first checks the `VAULT_ENABLED` environment variable to determine
@@ -33,12 +41,8 @@ pub async fn get_or_generate_keypair(
     secp: &Secp256k1<All>,
     secret_key_file: Option<std::path::PathBuf>,
 ) -> KeyPair {
-    let use_vault: bool = env::var("VAULT_ENABLED")
-        .unwrap_or("false".to_string())
-        .parse()
-        .unwrap();
     let secret_key: SecretKey;
-    if use_vault {
+    if use_vault() {
         secret_key = get_or_generate_secret_from_vault(&secp).await.unwrap();
     } else {
         secret_key = get_or_generate_secret_from_config(&secp, secret_key_file).unwrap();
@@ -46,13 +50,226 @@ pub async fn get_or_generate_keypair(
     return KeyPair::from_secret_key(&secp, secret_key);
 }
 
-async fn get_or_generate_secret_from_vault(secp: &Secp256k1<All>) -> anyhow::Result<SecretKey> {
+/// This function returns the `Keyring` of the oracle: its active key, as
+/// returned by `get_or_generate_keypair`, and the keys retired by
+/// `rotate_keyring`, which are stored next to it.
+///
+/// # Arguments
+///
+/// * `secp` - A reference to a `Secp256k1` context.
+/// * `secret_key_file` - An optional `PathBuf` that specifies the path to the secret key file.
+///
+/// # Returns
+///
+/// The `Keyring` of the oracle, or an error if retired keys cannot be read.
+pub async fn get_or_generate_keyring(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+) -> anyhow::Result<Keyring> {
+    let active = get_or_generate_keypair(secp, secret_key_file.clone()).await;
+    keyring_with_retired_keys(secp, active, &secret_key_file).await
+}
+
+/// This function returns the `Keyring` of the oracle like `get_or_generate_keyring`,
+/// but fails instead of generating a key if the oracle has none.
+///
+/// # Arguments
+///
+/// * `secp` - A reference to a `Secp256k1` context.
+/// * `secret_key_file` - An optional `PathBuf` that specifies the path to the secret key file.
+///
+/// # Returns
+///
+/// The `Keyring` of the oracle, or an error if it has no key or keys cannot be read.
+pub async fn read_keyring(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+) -> anyhow::Result<Keyring> {
+    let secret_key = read_secret_key(&secret_key_file)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no oracle key found, generate one with keygen"))?;
+    let active = KeyPair::from_secret_key(secp, secret_key);
+    keyring_with_retired_keys(secp, active, &secret_key_file).await
+}
+
+/// This function generates the key of an oracle which has none yet, and stores it
+/// in the secret key file (or in Vault when `VAULT_ENABLED` is set). Existing keys
+/// are never overwritten: `rotate_keyring` replaces them.
+///
+/// # Arguments
+///
+/// * `secp` - A reference to a `Secp256k1` context.
+/// * `secret_key_file` - An optional `PathBuf` that specifies the path to the secret key file.
+///
+/// # Returns
+///
+/// The `Keyring` of the oracle, or an error if it already has a key or the key cannot be stored.
+pub async fn generate_keyring(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+) -> anyhow::Result<Keyring> {
+    if read_secret_key(&secret_key_file).await?.is_some() {
+        anyhow::bail!("an oracle key already exists, replace it with rotate-key");
+    }
+    let new_key = secp.generate_keypair(&mut rand::thread_rng()).0;
+    write_secret_key(&secret_key_file, &new_key).await?;
+    read_keyring(secp, secret_key_file).await
+}
+
+/// This function rotates the key of the oracle: the active key is retired and
+/// replaced with a newly generated key, which announces events from then on.
+/// Retired keys are kept to attest to the events they announced.
+///
+/// The retired key is stored before the new key, so that an interrupted
+/// rotation never loses a key.
+///
+/// # Arguments
+///
+/// * `secp` - A reference to a `Secp256k1` context.
+/// * `secret_key_file` - An optional `PathBuf` that specifies the path to the secret key file.
+///
+/// # Returns
+///
+/// The `Keyring` of the oracle after rotation, or an error if the oracle has no
+/// key to rotate or keys cannot be stored.
+pub async fn rotate_keyring(
+    secp: &Secp256k1<All>,
+    secret_key_file: Option<PathBuf>,
+) -> anyhow::Result<Keyring> {
+    let active = read_secret_key(&secret_key_file)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("no oracle key to rotate, generate one with keygen"))?;
+    let mut retired = read_retired_keys(&secret_key_file).await?;
+    retired.push(RetiredKey {
+        secret_key: active.display_secret().to_string(),
+        retired_at: OffsetDateTime::now_utc(),
+    });
+    write_retired_keys(&secret_key_file, retired).await?;
+
+    let new_key = secp.generate_keypair(&mut rand::thread_rng()).0;
+    write_secret_key(&secret_key_file, &new_key).await?;
+    read_keyring(secp, secret_key_file).await
+}
+
+pub(crate) fn use_vault() -> bool {
+    env::var("VAULT_ENABLED")
+        .unwrap_or("false".to_string())
+        .parse()
+        .unwrap()
+}
+
+pub(crate) fn vault_key_path() -> String {
     let vault_data_path: String = env::var("VAULT_DATA_PATH").unwrap_or("".to_string());
-    let oracle_key = match vault_data_path.is_empty() {
+    match vault_data_path.is_empty() {
         true => format!("oracle/{}", gethostname().to_string_lossy()),
         false => format!("oracle/{}", vault_data_path),
-    };
-    let secret_mount = "secret";
+    }
+}
+
+fn secret_key_path(secret_key_file: &Option<PathBuf>) -> PathBuf {
+    secret_key_file
+        .clone()
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_SECRET_KEY_FILE))
+}
+
+// retired keys are stored next to the active key, e.g. in config/secret.retired.json
+fn retired_keys_path(secret_key_file: &Option<PathBuf>) -> PathBuf {
+    secret_key_path(secret_key_file).with_extension("retired.json")
+}
+
+async fn keyring_with_retired_keys(
+    secp: &Secp256k1<All>,
+    active: KeyPair,
+    secret_key_file: &Option<PathBuf>,
+) -> anyhow::Result<Keyring> {
+    let retired = read_retired_keys(secret_key_file)
+        .await?
+        .into_iter()
+        .map(|key| {
+            let secret_key = SecretKey::from_str(&key.secret_key)?;
+            Ok((KeyPair::from_secret_key(secp, secret_key), key.retired_at))
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    Ok(Keyring::new(active, retired))
+}
+
+// reads the active key without generating it, `None` if the oracle has none
+async fn read_secret_key(secret_key_file: &Option<PathBuf>) -> anyhow::Result<Option<SecretKey>> {
+    if use_vault() {
+        return match get_secret_key(&vault_key_path(), SECRET_MOUNT).await {
+            Ok(key) => Ok(Some(SecretKey::from_str(&key.private_key_value)?)),
+            Err(ClientError::APIError { code: 404, .. }) => Ok(None),
+            Err(err) => Err(err.into()),
+        };
+    }
+    let path = secret_key_path(secret_key_file);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let mut secret_key = String::new();
+    File::open(path)?.read_to_string(&mut secret_key)?;
+    secret_key.retain(|c| !c.is_whitespace());
+    Ok(Some(SecretKey::from_str(&secret_key)?))
+}
+
+async fn write_secret_key(
+    secret_key_file: &Option<PathBuf>,
+    secret_key: &SecretKey,
+) -> anyhow::Result<()> {
+    let secret_key = secret_key.display_secret().to_string();
+    if use_vault() {
+        set_secret_key(
+            &vault_key_path(),
+            SECRET_MOUNT,
+            OraclePrivateKey {
+                private_key_value: secret_key,
+            },
+        )
+        .await?;
+        return Ok(());
+    }
+    let path = secret_key_path(secret_key_file);
+    info!("writing new secret key to {}", path.to_string_lossy());
+    File::create(path)?.write_all(secret_key.as_bytes())?;
+    Ok(())
+}
+
+async fn read_retired_keys(secret_key_file: &Option<PathBuf>) -> anyhow::Result<Vec<RetiredKey>> {
+    if use_vault() {
+        let path = format!("{}/retired", vault_key_path());
+        return match get_retired_keys(&path, SECRET_MOUNT).await {
+            Ok(retired) => Ok(retired.keys),
+            Err(ClientError::APIError { code: 404, .. }) => Ok(vec![]),
+            Err(err) => Err(err.into()),
+        };
+    }
+    let path = retired_keys_path(secret_key_file);
+    if !path.exists() {
+        return Ok(vec![]);
+    }
+    let mut retired = String::new();
+    File::open(path)?.read_to_string(&mut retired)?;
+    Ok(serde_json::from_str(&retired)?)
+}
+
+async fn write_retired_keys(
+    secret_key_file: &Option<PathBuf>,
+    retired: Vec<RetiredKey>,
+) -> anyhow::Result<()> {
+    if use_vault() {
+        let path = format!("{}/retired", vault_key_path());
+        set_retired_keys(&path, SECRET_MOUNT, &RetiredOracleKeys { keys: retired }).await?;
+        return Ok(());
+    }
+    let path = retired_keys_path(secret_key_file);
+    info!("writing retired keys to {}", path.to_string_lossy());
+    File::create(path)?.write_all(&serde_json::to_vec_pretty(&retired)?)?;
+    Ok(())
+}
+
+async fn get_or_generate_secret_from_vault(secp: &Secp256k1<All>) -> anyhow::Result<SecretKey> {
+    let oracle_key = vault_key_path();
+    let secret_mount = SECRET_MOUNT;
     let vault_key: OraclePrivateKey = match get_secret_key(&oracle_key, secret_mount).await {
         Ok(res) => res,
         Err(err) => match err {
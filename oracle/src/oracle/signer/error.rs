@@ -0,0 +1,55 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, SignerError>;
+
+#[derive(Debug, Display, Error)]
+pub enum SignerError {
+    /// secp256k1 upstream error: {0}
+    Secp256k1UpstreamError(#[from] secp256k1_zkp::UpstreamError),
+
+    /// invalid announcement: {0}
+    InvalidAnnouncementError(String),
+
+    /// invalid announcement: {0}
+    VerificationError(#[from] crate::VerificationError),
+
+    /// event was announced with key {0}, which the signer does not hold
+    UnknownKeyError(String),
+
+    /// nonces of event {0} are not those of the signer
+    NonceMismatchError(String),
+
+    /// event {0} has {1} nonces but {2} outcomes to attest
+    OutcomeCountMismatchError(String, usize, usize),
+
+    /// event {0} cannot be attested yet, it matures in {1}
+    PrematureAttestationError(String, time::Duration),
+
+    /// nonces of event {0} already signed other outcomes
+    NonceReuseError(String),
+
+    /// event {0} was already announced with other terms
+    AnnouncementConflictError(String),
+
+    /// event {0} was not announced by the signer
+    UnknownEventError(String),
+
+    /// invalid attestation: {0}
+    InvalidAttestationError(String),
+
+    /// signer database error: {0}
+    DatabaseError(#[from] sled::Error),
+
+    /// signer connection error: {0}
+    IoError(#[from] std::io::Error),
+
+    /// json serialization/deserialization error: {0}
+    SerdeJsonError(#[from] serde_json::Error),
+
+    /// malformed signer message: {0}
+    MalformedMessageError(String),
+
+    /// remote signer error: {0}
+    RemoteError(String),
+}
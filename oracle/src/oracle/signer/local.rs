@@ -0,0 +1,173 @@
+use super::{announcement_of, Result, Signer, SignerError, SignerKey, SigningRequest};
+use crate::{
+    oracle::{
+        oracle_queryable::messaging::OracleAnnouncementHash,
+        oracle_scheduler::{derive_sk_nonces, nonces_of},
+        Keyring,
+    },
+    verify_announcement, AssetPair, OracleEvent,
+};
+use core::ptr;
+use secp256k1_sys::{
+    types::{c_int, c_uchar, c_void, size_t},
+    CPtr, SchnorrSigExtraParams,
+};
+use secp256k1_zkp::{
+    constants::SCHNORR_SIGNATURE_SIZE, hashes::sha256, schnorr::Signature as SchnorrSignature, All,
+    KeyPair, Message, Secp256k1, Signing, XOnlyPublicKey as SchnorrPublicKey,
+};
+
+extern "C" fn constant_nonce_fn(
+    nonce32: *mut c_uchar,
+    _: *const c_uchar,
+    _: size_t,
+    _: *const c_uchar,
+    _: *const c_uchar,
+    _: *const c_uchar,
+    _: size_t,
+    data: *mut c_void,
+) -> c_int {
+    unsafe {
+        ptr::copy_nonoverlapping(data as *const c_uchar, nonce32, 32);
+    }
+    1
+}
+
+fn sign_schnorr_with_nonce<S: Signing>(
+    secp: &Secp256k1<S>,
+    msg: &Message,
+    keypair: &KeyPair,
+    nonce: &[u8; 32],
+) -> SchnorrSignature {
+    unsafe {
+        let mut sig = [0u8; SCHNORR_SIGNATURE_SIZE];
+        let nonce_params =
+            SchnorrSigExtraParams::new(Some(constant_nonce_fn), nonce.as_c_ptr() as *const c_void);
+        assert_eq!(
+            1,
+            secp256k1_sys::secp256k1_schnorrsig_sign_custom(
+                *secp.ctx(),
+                sig.as_mut_c_ptr(),
+                msg.as_c_ptr(),
+                msg.len(),
+                keypair.as_ptr(),
+                &nonce_params as *const SchnorrSigExtraParams
+            )
+        );
+
+        SchnorrSignature::from_slice(&sig).unwrap()
+    }
+}
+
+/// Signer holding the oracle keys in process memory.
+///
+/// It checks that it signs with the nonces derived for each event, but leaves the attestation
+/// policies, e.g. maturity, to the oracle.
+#[derive(Clone)]
+pub struct LocalSigner {
+    keyring: Keyring,
+    keys: Vec<SignerKey>,
+    secp: Secp256k1<All>,
+}
+
+impl LocalSigner {
+    /// Creates the signer of the keys of `keyring`.
+    pub fn new(keyring: Keyring, secp: Secp256k1<All>) -> Self {
+        let keys = keyring
+            .keys()
+            .iter()
+            .map(|key| SignerKey {
+                public_key: key.keypair.public_key(),
+                activated_at: key.activated_at,
+                retired_at: key.retired_at,
+            })
+            .collect();
+        LocalSigner {
+            keyring,
+            keys,
+            secp,
+        }
+    }
+}
+
+impl Signer for LocalSigner {
+    fn keys(&self) -> Vec<SignerKey> {
+        self.keys.clone()
+    }
+
+    fn nonces(
+        &self,
+        asset_pair: &AssetPair,
+        event_id: &str,
+        nb_nonces: usize,
+    ) -> Result<Vec<SchnorrPublicKey>> {
+        let sk_nonces = derive_sk_nonces(self.keyring.active(), asset_pair, event_id, nb_nonces);
+        Ok(nonces_of(&self.secp, &sk_nonces)?)
+    }
+
+    fn sign_announcement(
+        &self,
+        asset_pair: &AssetPair,
+        oracle_event: &OracleEvent,
+    ) -> Result<SchnorrSignature> {
+        let nb_nonces = oracle_event.event_descriptor.nb_nonces();
+        if oracle_event.nonces != self.nonces(asset_pair, &oracle_event.event_id, nb_nonces)? {
+            return Err(SignerError::NonceMismatchError(
+                oracle_event.event_id.clone(),
+            ));
+        }
+        Ok(self.secp.sign_schnorr(
+            &Message::from_hashed_data::<OracleAnnouncementHash>(&oracle_event.encode()),
+            self.keyring.active(),
+        ))
+    }
+
+    fn sign_outcomes(&self, request: &SigningRequest) -> Result<Vec<SchnorrSignature>> {
+        let announcement = announcement_of(request)?;
+        verify_announcement(&self.secp, &announcement)?;
+        let event_id = &announcement.oracle_event.event_id;
+
+        // events are attested with the key which announced them, even if it was retired since
+        let public_key = SchnorrPublicKey::from_slice(&announcement.oracle_public_key.serialize())?;
+        let keypair = self
+            .keyring
+            .get(&public_key)
+            .ok_or_else(|| SignerError::UnknownKeyError(public_key.to_string()))?;
+
+        // each outcome is signed with its own nonce, a missing one would change the outcome
+        let nonces = &announcement.oracle_event.oracle_nonces;
+        if request.outcomes.len() != nonces.len() {
+            return Err(SignerError::OutcomeCountMismatchError(
+                event_id.clone(),
+                nonces.len(),
+                request.outcomes.len(),
+            ));
+        }
+        // events announced before nonces were derived have their nonces stored
+        let sk_nonces = match &request.sk_nonces {
+            Some(sk_nonces) => sk_nonces.clone(),
+            None => derive_sk_nonces(keypair, &request.asset_pair, event_id, nonces.len()),
+        };
+        if nonces_of(&self.secp, &sk_nonces)?
+            .iter()
+            .map(|nonce| nonce.serialize())
+            .ne(nonces.iter().map(|nonce| nonce.serialize()))
+        {
+            return Err(SignerError::NonceMismatchError(event_id.clone()));
+        }
+
+        Ok(request
+            .outcomes
+            .iter()
+            .zip(&sk_nonces)
+            .map(|(outcome, sk_nonce)| {
+                sign_schnorr_with_nonce(
+                    &self.secp,
+                    &Message::from_hashed_data::<sha256::Hash>(outcome.as_bytes()),
+                    keypair,
+                    sk_nonce,
+                )
+            })
+            .collect())
+    }
+}
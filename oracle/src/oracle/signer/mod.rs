@@ -0,0 +1,83 @@
+use crate::{oracle::decode_announcement, AssetPair, OracleEvent};
+use dlc_messages::oracle_msgs::OracleAnnouncement;
+use secp256k1_zkp::{schnorr::Signature as SchnorrSignature, XOnlyPublicKey as SchnorrPublicKey};
+use time::OffsetDateTime;
+
+mod error;
+mod local;
+mod remote;
+pub use error::{Result, SignerError};
+pub use local::LocalSigner;
+pub use remote::{RemoteSigner, SignerPolicy, SignerServer};
+
+/// A public key of a signer and the time window it announced events in, see
+/// [`crate::oracle::OracleKey`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignerKey {
+    pub public_key: SchnorrPublicKey,
+    /// start of the window, unknown for the first key of the oracle
+    pub activated_at: Option<OffsetDateTime>,
+    /// end of the window, `None` for the active key
+    pub retired_at: Option<OffsetDateTime>,
+}
+
+/// Outcomes of an announced event to sign, see [`Signer::sign_outcomes`].
+#[derive(Clone, Debug)]
+pub struct SigningRequest {
+    pub asset_pair: AssetPair,
+    /// announcement of the event, as stored
+    pub announcement: Vec<u8>,
+    /// outcomes to sign, one per announced nonce
+    pub outcomes: Vec<String>,
+    /// reason to attest before maturation, if the oracle does
+    pub early_attestation_reason: Option<String>,
+    /// secret nonces of events announced before nonces were derived from the oracle key
+    pub sk_nonces: Option<Vec<[u8; 32]>>,
+}
+
+/// Holder of the oracle keys, which signs announcements and attestations for the oracle.
+///
+/// [`LocalSigner`] holds the keys in the oracle process. [`RemoteSigner`] asks a separate signer
+/// process instead, which enforces its own policies, so that the keys never are in the memory of
+/// the process serving the oracle API.
+pub trait Signer: Send + Sync {
+    /// Returns the keys of the signer, ordered by activation, the active key last.
+    ///
+    /// Keys may be rotated while the oracle runs, so they are not cached by the oracle: a remote
+    /// signer asks the signer process, which blocks like signing.
+    fn keys(&self) -> Vec<SignerKey>;
+
+    /// Returns the nonces the active key announces the event `event_id` of `asset_pair` with.
+    fn nonces(
+        &self,
+        asset_pair: &AssetPair,
+        event_id: &str,
+        nb_nonces: usize,
+    ) -> Result<Vec<SchnorrPublicKey>>;
+
+    /// Signs the announcement of `oracle_event` with the active key. The nonces of the event must
+    /// be those returned by [`Signer::nonces`].
+    fn sign_announcement(
+        &self,
+        asset_pair: &AssetPair,
+        oracle_event: &OracleEvent,
+    ) -> Result<SchnorrSignature>;
+
+    /// Signs each outcome of `request` with the nonce announced for it, by the key which announced
+    /// the event, which must be one of the signer.
+    fn sign_outcomes(&self, request: &SigningRequest) -> Result<Vec<SchnorrSignature>>;
+
+    /// Returns the active key, which announces new events.
+    fn public_key(&self) -> SchnorrPublicKey {
+        self.keys()
+            .last()
+            .expect("signer has an active key")
+            .public_key
+    }
+}
+
+// decodes the announcement of a signing request
+fn announcement_of(request: &SigningRequest) -> Result<OracleAnnouncement> {
+    decode_announcement(&request.announcement)
+        .map_err(|e| SignerError::InvalidAnnouncementError(e.to_string()))
+}
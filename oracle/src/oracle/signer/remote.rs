@@ -0,0 +1,786 @@
+use super::{announcement_of, LocalSigner, Result, Signer, SignerError, SignerKey, SigningRequest};
+use crate::{
+    oracle::{decode_announcement, decode_attestation, StoredEvent},
+    AssetPair, OracleEvent, OracleEventDescriptor,
+};
+use hex::{FromHex, ToHex};
+use lightning::util::ser::Writeable;
+use log::{info, warn};
+use parking_lot::Mutex;
+use secp256k1_zkp::{
+    hashes::{sha256, Hash},
+    schnorr::Signature as SchnorrSignature,
+    XOnlyPublicKey as SchnorrPublicKey,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, Permissions},
+    io::{self, BufRead, BufReader, Write},
+    os::unix::{
+        fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+        net::UnixStream,
+    },
+    path::{Path, PathBuf},
+    str::FromStr,
+    sync::Arc,
+    time::Duration as StdDuration,
+};
+use time::{Duration, OffsetDateTime};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+
+// how long the oracle waits for the signer process to answer a request
+const REQUEST_TIMEOUT: StdDuration = StdDuration::from_secs(30);
+// marks the signer as seeded with the events stored before it was set up, see `SignerServer::seed`
+const SEEDED_KEY: &[u8] = b"seeded";
+
+/// A request of the oracle to the signer process, sent as a line of JSON.
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Request {
+    Keys,
+    Nonces {
+        asset_pair: AssetPair,
+        event_id: String,
+        nb_nonces: usize,
+    },
+    SignAnnouncement {
+        asset_pair: AssetPair,
+        nonces: Vec<String>,
+        #[serde(with = "time::serde::rfc3339")]
+        maturation: OffsetDateTime,
+        event_descriptor: OracleEventDescriptor,
+        event_id: String,
+    },
+    SignOutcomes {
+        asset_pair: AssetPair,
+        announcement: String,
+        outcomes: Vec<String>,
+        early_attestation_reason: Option<String>,
+        sk_nonces: Option<Vec<String>>,
+    },
+}
+
+/// The answer of the signer process to a [`Request`], sent as a line of JSON.
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Response {
+    Keys {
+        keys: Vec<RemoteKey>,
+    },
+    Nonces {
+        nonces: Vec<String>,
+    },
+    Signatures {
+        signatures: Vec<String>,
+    },
+    /// the request goes against the policy of the signer
+    Refused {
+        event_id: String,
+        refusal: Refusal,
+    },
+    Error {
+        message: String,
+    },
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+enum Refusal {
+    Premature { matures_in_seconds: i64 },
+    NonceReuse,
+    AnnouncementConflict,
+    UnknownEvent,
+}
+
+#[derive(Deserialize, Serialize)]
+struct RemoteKey {
+    public_key: String,
+    #[serde(with = "time::serde::rfc3339::option")]
+    activated_at: Option<OffsetDateTime>,
+    #[serde(with = "time::serde::rfc3339::option")]
+    retired_at: Option<OffsetDateTime>,
+}
+
+fn malformed(e: impl ToString) -> SignerError {
+    SignerError::MalformedMessageError(e.to_string())
+}
+
+fn parse_all<T: FromStr>(values: &[String]) -> Result<Vec<T>>
+where
+    T::Err: ToString,
+{
+    values
+        .iter()
+        .map(|value| value.parse().map_err(malformed))
+        .collect()
+}
+
+fn encode_all<T: ToString>(values: &[T]) -> Vec<String> {
+    values.iter().map(ToString::to_string).collect()
+}
+
+impl From<&SignerKey> for RemoteKey {
+    fn from(key: &SignerKey) -> Self {
+        RemoteKey {
+            public_key: key.public_key.to_string(),
+            activated_at: key.activated_at,
+            retired_at: key.retired_at,
+        }
+    }
+}
+
+impl TryFrom<RemoteKey> for SignerKey {
+    type Error = SignerError;
+
+    fn try_from(key: RemoteKey) -> Result<Self> {
+        Ok(SignerKey {
+            public_key: key.public_key.parse().map_err(malformed)?,
+            activated_at: key.activated_at,
+            retired_at: key.retired_at,
+        })
+    }
+}
+
+/// Signer asking a [`SignerServer`] process to sign, over the unix socket it listens on.
+///
+/// Requests block the calling thread until the signer answers, for at most 30 seconds, so the
+/// oracle only calls signers off the async runtime, see [`crate::oracle::RecordsUpdate`].
+///
+/// Keys are asked to the signer process on every [`Signer::keys`] call, so that keys rotated by
+/// the signer process are used at once.
+#[derive(Clone)]
+pub struct RemoteSigner {
+    socket_path: PathBuf,
+    // keys last returned by the signer process, in case it cannot be asked again
+    keys: Arc<Mutex<Vec<SignerKey>>>,
+}
+
+impl RemoteSigner {
+    /// Connects to the signer process listening on `socket_path`, retrieving its keys.
+    pub fn connect(socket_path: impl Into<PathBuf>) -> Result<Self> {
+        let signer = RemoteSigner {
+            socket_path: socket_path.into(),
+            keys: Arc::new(Mutex::new(vec![])),
+        };
+        *signer.keys.lock() = signer.fetch_keys()?;
+        info!(
+            "connected to signer at {}, pubkey is {}",
+            signer.socket_path.to_string_lossy(),
+            signer.public_key()
+        );
+        Ok(signer)
+    }
+
+    // asks the keys of the signer process
+    fn fetch_keys(&self) -> Result<Vec<SignerKey>> {
+        let keys = match self.request(&Request::Keys)? {
+            Response::Keys { keys } => keys
+                .into_iter()
+                .map(SignerKey::try_from)
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(malformed("unexpected response to a keys request")),
+        };
+        if keys.is_empty() {
+            return Err(malformed("signer has no key"));
+        }
+        Ok(keys)
+    }
+
+    // sends `request` on a new connection, returning the response unless it is an error
+    fn request(&self, request: &Request) -> Result<Response> {
+        let stream = UnixStream::connect(&self.socket_path)?;
+        stream.set_read_timeout(Some(REQUEST_TIMEOUT))?;
+        stream.set_write_timeout(Some(REQUEST_TIMEOUT))?;
+        let mut message = serde_json::to_vec(request)?;
+        message.push(b'\n');
+        (&stream).write_all(&message)?;
+
+        let mut line = String::new();
+        BufReader::new(&stream).read_line(&mut line)?;
+        match serde_json::from_str(&line)? {
+            Response::Refused { event_id, refusal } => Err(match refusal {
+                Refusal::Premature { matures_in_seconds } => {
+                    SignerError::PrematureAttestationError(
+                        event_id,
+                        Duration::seconds(matures_in_seconds),
+                    )
+                }
+                Refusal::NonceReuse => SignerError::NonceReuseError(event_id),
+                Refusal::AnnouncementConflict => SignerError::AnnouncementConflictError(event_id),
+                Refusal::UnknownEvent => SignerError::UnknownEventError(event_id),
+            }),
+            Response::Error { message } => Err(SignerError::RemoteError(message)),
+            response => Ok(response),
+        }
+    }
+
+    // sends `request`, expecting signatures in response
+    fn request_signatures(&self, request: &Request) -> Result<Vec<SchnorrSignature>> {
+        match self.request(request)? {
+            Response::Signatures { signatures } => parse_all(&signatures),
+            _ => Err(malformed("unexpected response to a signing request")),
+        }
+    }
+}
+
+impl Signer for RemoteSigner {
+    fn keys(&self) -> Vec<SignerKey> {
+        match self.fetch_keys() {
+            Ok(keys) => {
+                let mut known = self.keys.lock();
+                if known.last() != keys.last() {
+                    info!(
+                        "signer key rotated, pubkey is now {}",
+                        keys.last().expect("signer has a key").public_key
+                    );
+                }
+                *known = keys.clone();
+                keys
+            }
+            Err(e) => {
+                warn!(
+                    "could not ask the keys of the signer, using the last ones: {}",
+                    e
+                );
+                self.keys.lock().clone()
+            }
+        }
+    }
+
+    fn nonces(
+        &self,
+        asset_pair: &AssetPair,
+        event_id: &str,
+        nb_nonces: usize,
+    ) -> Result<Vec<SchnorrPublicKey>> {
+        match self.request(&Request::Nonces {
+            asset_pair: asset_pair.clone(),
+            event_id: event_id.to_string(),
+            nb_nonces,
+        })? {
+            Response::Nonces { nonces } => parse_all(&nonces),
+            _ => Err(malformed("unexpected response to a nonces request")),
+        }
+    }
+
+    fn sign_announcement(
+        &self,
+        asset_pair: &AssetPair,
+        oracle_event: &OracleEvent,
+    ) -> Result<SchnorrSignature> {
+        self.request_signatures(&Request::SignAnnouncement {
+            asset_pair: asset_pair.clone(),
+            nonces: encode_all(&oracle_event.nonces),
+            maturation: oracle_event.maturation,
+            event_descriptor: oracle_event.event_descriptor.clone(),
+            event_id: oracle_event.event_id.clone(),
+        })?
+        .pop()
+        .ok_or_else(|| malformed("no announcement signature"))
+    }
+
+    fn sign_outcomes(&self, request: &SigningRequest) -> Result<Vec<SchnorrSignature>> {
+        self.request_signatures(&Request::SignOutcomes {
+            asset_pair: request.asset_pair.clone(),
+            announcement: request.announcement.encode_hex(),
+            outcomes: request.outcomes.clone(),
+            early_attestation_reason: request.early_attestation_reason.clone(),
+            sk_nonces: request.sk_nonces.as_ref().map(|sk_nonces| {
+                sk_nonces
+                    .iter()
+                    .map(|sk_nonce| sk_nonce.encode_hex())
+                    .collect()
+            }),
+        })
+    }
+}
+
+/// Policies the signer process enforces, whatever the oracle requests.
+#[derive(Clone, Copy, Debug)]
+pub struct SignerPolicy {
+    /// how long before maturation events may be attested, see `attestation_grace_period`
+    pub attestation_grace_period: Duration,
+    /// whether events may be attested before that when the oracle gives a reason
+    pub allow_early_attestation: bool,
+}
+
+/// The signer process, which holds the oracle keys and signs for [`RemoteSigner`]s connecting to
+/// its unix socket.
+///
+/// Besides the checks of [`LocalSigner`], it refuses to attest events before their maturation as
+/// set by its [`SignerPolicy`], to sign two different outcomes with a nonce, to announce an event
+/// again with other terms, e.g. an earlier maturation, and to attest events it did not announce.
+/// The outcomes signed with each nonce and the announced events are recorded in its own database,
+/// so that these hold across restarts, along with the events stored before the signer was set up,
+/// see [`SignerServer::seed`].
+pub struct SignerServer {
+    signer: LocalSigner,
+    policy: SignerPolicy,
+    // hash of the announced event, by its first nonce
+    announced: sled::Tree,
+    // signed outcome, by nonce
+    signed: sled::Tree,
+    // markers of the setup of the signer
+    state: sled::Tree,
+    // held while signing, so that concurrent requests cannot both use a nonce
+    lock: Mutex<()>,
+}
+
+impl SignerServer {
+    /// Creates the signer process of `signer`, recording what it signs in `db`.
+    pub fn new(signer: LocalSigner, policy: SignerPolicy, db: &sled::Db) -> Result<Self> {
+        Ok(SignerServer {
+            signer,
+            policy,
+            announced: db.open_tree("announced")?,
+            signed: db.open_tree("signed")?,
+            state: db.open_tree("state")?,
+            lock: Mutex::new(()),
+        })
+    }
+
+    /// Returns whether the signer was seeded with the events stored before it was set up.
+    pub fn is_seeded(&self) -> Result<bool> {
+        Ok(self.state.contains_key(SEEDED_KEY)?)
+    }
+
+    /// Records `events`, stored before the signer was set up, as if the signer had announced them
+    /// and signed the outcomes they are attested to, returning how many were recorded. They may
+    /// then be attested, but never to other outcomes.
+    ///
+    /// The signer refuses to attest the events it has no record of, so it is seeded with the
+    /// stored events before it serves requests for the first time.
+    pub fn seed(&self, events: &[StoredEvent]) -> Result<usize> {
+        for event in events {
+            self.record_event(&event.announcement, event.attestation.as_deref())?;
+        }
+        self.announced.flush()?;
+        self.signed.flush()?;
+        self.state.insert(SEEDED_KEY, Vec::<u8>::new())?;
+        self.state.flush()?;
+        Ok(events.len())
+    }
+
+    // records the event of `announcement` as announced, and the outcomes of `attestation` as
+    // signed, keeping the records the signer already has
+    fn record_event(&self, announcement: &[u8], attestation: Option<&[u8]>) -> Result<()> {
+        let announcement = decode_announcement(announcement)
+            .map_err(|e| SignerError::InvalidAnnouncementError(e.to_string()))?;
+        let event_id = &announcement.oracle_event.event_id;
+        let nonces = &announcement.oracle_event.oracle_nonces;
+        let first_nonce = nonces
+            .first()
+            .ok_or_else(|| SignerError::InvalidAnnouncementError("no nonce".to_string()))?;
+        let event_hash = sha256::Hash::hash(&announcement.oracle_event.encode());
+
+        let _guard = self.lock.lock();
+        match self.announced.get(first_nonce.serialize())? {
+            None => {
+                self.announced
+                    .insert(first_nonce.serialize(), &event_hash[..])?;
+            }
+            Some(announced) if announced != event_hash[..] => warn!(
+                "event {} was recorded as announced with other terms, keeping that record",
+                event_id
+            ),
+            Some(_) => {}
+        }
+        if let Some(attestation) = attestation {
+            let attestation = decode_attestation(attestation)
+                .map_err(|e| SignerError::InvalidAttestationError(e.to_string()))?;
+            for (nonce, outcome) in nonces.iter().zip(&attestation.outcomes) {
+                match self.signed.get(nonce.serialize())? {
+                    None => {
+                        self.signed.insert(nonce.serialize(), outcome.as_bytes())?;
+                    }
+                    Some(signed) if signed != outcome.as_bytes() => warn!(
+                        "a nonce of event {} was recorded as signing another outcome, keeping \
+                         that record",
+                        event_id
+                    ),
+                    Some(_) => {}
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Serves requests on a unix socket created at `socket_path`, replacing any stale one, which
+    /// only the user running the signer may connect to. Fails if anything else than a socket is
+    /// at `socket_path`.
+    pub async fn serve(self, socket_path: &Path) -> Result<()> {
+        match fs::symlink_metadata(socket_path) {
+            Ok(metadata) if metadata.file_type().is_socket() => fs::remove_file(socket_path)?,
+            Ok(_) => {
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "{} exists and is not a socket",
+                        socket_path.to_string_lossy()
+                    ),
+                )
+                .into())
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+        // the socket is bound in a directory only the signer's user may enter, and only moved in
+        // place once restricted, so that other users cannot connect in between
+        let file_name = socket_path.file_name().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::InvalidInput, "socket path has no file name")
+        })?;
+        let private_dir =
+            socket_path.with_file_name(format!(".{}.tmp", file_name.to_string_lossy()));
+        if private_dir.exists() {
+            fs::remove_dir_all(&private_dir)?;
+        }
+        fs::DirBuilder::new().mode(0o700).create(&private_dir)?;
+        let private_socket_path = private_dir.join("socket");
+        let listener = tokio::net::UnixListener::bind(&private_socket_path)?;
+        fs::set_permissions(&private_socket_path, Permissions::from_mode(0o600))?;
+        fs::rename(&private_socket_path, socket_path)?;
+        fs::remove_dir(&private_dir)?;
+        info!(
+            "signer listening on {}, pubkey is {}",
+            socket_path.to_string_lossy(),
+            self.signer.public_key()
+        );
+
+        let server = Arc::new(self);
+        loop {
+            let (stream, _) = listener.accept().await?;
+            let server = server.clone();
+            tokio::spawn(async move {
+                if let Err(e) = server.serve_connection(stream).await {
+                    warn!("signer connection failed: {}", e);
+                }
+            });
+        }
+    }
+
+    async fn serve_connection(self: Arc<Self>, stream: tokio::net::UnixStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        let mut lines = tokio::io::BufReader::new(reader).lines();
+        while let Some(line) = lines.next_line().await? {
+            let response = match serde_json::from_str(&line) {
+                // signing waits for the signed outcomes to be flushed to disk, so it runs off the
+                // async runtime
+                Ok(request) => {
+                    let server = self.clone();
+                    tokio::task::spawn_blocking(move || server.respond(request))
+                        .await
+                        .unwrap_or_else(|e| Response::Error {
+                            message: format!("signer task failed: {}", e),
+                        })
+                }
+                Err(e) => Response::Error {
+                    message: format!("invalid request: {}", e),
+                },
+            };
+            let mut message = serde_json::to_vec(&response)?;
+            message.push(b'\n');
+            writer.write_all(&message).await?;
+        }
+        Ok(())
+    }
+
+    fn respond(&self, request: Request) -> Response {
+        let (event_id, refusal) = match self.handle(request) {
+            Ok(response) => return response,
+            Err(SignerError::PrematureAttestationError(event_id, matures_in)) => (
+                event_id,
+                Refusal::Premature {
+                    matures_in_seconds: matures_in.whole_seconds(),
+                },
+            ),
+            Err(SignerError::NonceReuseError(event_id)) => (event_id, Refusal::NonceReuse),
+            Err(SignerError::AnnouncementConflictError(event_id)) => {
+                (event_id, Refusal::AnnouncementConflict)
+            }
+            Err(SignerError::UnknownEventError(event_id)) => (event_id, Refusal::UnknownEvent),
+            Err(e) => {
+                warn!("signer request failed: {}", e);
+                return Response::Error {
+                    message: e.to_string(),
+                };
+            }
+        };
+        warn!("refused to sign for event {}", event_id);
+        Response::Refused { event_id, refusal }
+    }
+
+    fn handle(&self, request: Request) -> Result<Response> {
+        match request {
+            Request::Keys => Ok(Response::Keys {
+                keys: self.signer.keys().iter().map(RemoteKey::from).collect(),
+            }),
+            Request::Nonces {
+                asset_pair,
+                event_id,
+                nb_nonces,
+            } => Ok(Response::Nonces {
+                nonces: encode_all(&self.signer.nonces(&asset_pair, &event_id, nb_nonces)?),
+            }),
+            Request::SignAnnouncement {
+                asset_pair,
+                nonces,
+                maturation,
+                event_descriptor,
+                event_id,
+            } => {
+                let oracle_event = OracleEvent {
+                    nonces: parse_all(&nonces)?,
+                    maturation,
+                    event_descriptor,
+                    event_id,
+                };
+                let signature = self.sign_announcement(&asset_pair, &oracle_event)?;
+                Ok(Response::Signatures {
+                    signatures: vec![signature.to_string()],
+                })
+            }
+            Request::SignOutcomes {
+                asset_pair,
+                announcement,
+                outcomes,
+                early_attestation_reason,
+                sk_nonces,
+            } => {
+                let request = SigningRequest {
+                    asset_pair,
+                    announcement: Vec::from_hex(&announcement).map_err(malformed)?,
+                    outcomes,
+                    early_attestation_reason,
+                    sk_nonces: sk_nonces
+                        .map(|sk_nonces| {
+                            sk_nonces
+                                .iter()
+                                .map(<[u8; 32]>::from_hex)
+                                .collect::<std::result::Result<Vec<_>, _>>()
+                        })
+                        .transpose()
+                        .map_err(malformed)?,
+                };
+                Ok(Response::Signatures {
+                    signatures: encode_all(&self.sign_outcomes(&request)?),
+                })
+            }
+        }
+    }
+
+    // signs the announcement of `oracle_event`, unless the event was announced with other terms
+    fn sign_announcement(
+        &self,
+        asset_pair: &AssetPair,
+        oracle_event: &OracleEvent,
+    ) -> Result<SchnorrSignature> {
+        let first_nonce = oracle_event
+            .nonces
+            .first()
+            .ok_or_else(|| SignerError::InvalidAnnouncementError("no nonce".to_string()))?
+            .serialize();
+        let event_hash = sha256::Hash::hash(&oracle_event.encode());
+
+        let _guard = self.lock.lock();
+        if let Some(announced) = self.announced.get(first_nonce)? {
+            if announced != event_hash[..] {
+                return Err(SignerError::AnnouncementConflictError(
+                    oracle_event.event_id.clone(),
+                ));
+            }
+        }
+        let signature = self.signer.sign_announcement(asset_pair, oracle_event)?;
+        self.announced.insert(first_nonce, &event_hash[..])?;
+        self.announced.flush()?;
+        Ok(signature)
+    }
+
+    // signs the outcomes of `request` if the event is mature and its nonces did not sign other
+    // outcomes, recording the outcomes before returning their signatures
+    fn sign_outcomes(&self, request: &SigningRequest) -> Result<Vec<SchnorrSignature>> {
+        let announcement = announcement_of(request)?;
+        let event_id = announcement.oracle_event.event_id.clone();
+        let nonces = &announcement.oracle_event.oracle_nonces;
+
+        let maturation = OffsetDateTime::from_unix_timestamp(
+            announcement.oracle_event.event_maturity_epoch.into(),
+        )
+        .map_err(|e| SignerError::InvalidAnnouncementError(e.to_string()))?;
+        let now = OffsetDateTime::now_utc();
+        if now < maturation - self.policy.attestation_grace_period {
+            match &request.early_attestation_reason {
+                Some(reason) if self.policy.allow_early_attestation => warn!(
+                    "signing attestation of event {} {} before maturation, reason: {}",
+                    event_id,
+                    maturation - now,
+                    reason
+                ),
+                _ => {
+                    return Err(SignerError::PrematureAttestationError(
+                        event_id,
+                        maturation - now,
+                    ))
+                }
+            }
+        }
+
+        let first_nonce = nonces
+            .first()
+            .ok_or_else(|| SignerError::InvalidAnnouncementError("no nonce".to_string()))?;
+        let event_hash = sha256::Hash::hash(&announcement.oracle_event.encode());
+
+        let _guard = self.lock.lock();
+        // only the events the signer announced, or was seeded with, are attested
+        match self.announced.get(first_nonce.serialize())? {
+            Some(announced) if announced == event_hash[..] => {}
+            Some(_) => return Err(SignerError::AnnouncementConflictError(event_id)),
+            None => return Err(SignerError::UnknownEventError(event_id)),
+        }
+        for (nonce, outcome) in nonces.iter().zip(&request.outcomes) {
+            if let Some(signed) = self.signed.get(nonce.serialize())? {
+                if signed != outcome.as_bytes() {
+                    return Err(SignerError::NonceReuseError(event_id));
+                }
+            }
+        }
+        let signatures = self.signer.sign_outcomes(request)?;
+
+        // signing another outcome with a nonce would reveal the key, so outcomes are recorded
+        // durably before their signatures leave the signer
+        let mut batch = sled::Batch::default();
+        for (nonce, outcome) in nonces.iter().zip(&request.outcomes) {
+            batch.insert(&nonce.serialize()[..], outcome.as_bytes());
+        }
+        self.signed.apply_batch(batch)?;
+        self.signed.flush()?;
+        Ok(signatures)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::oracle::{
+        oracle_scheduler::{build_announcement, build_attestation},
+        test_utils::local_signer,
+    };
+
+    fn server(allow_early_attestation: bool) -> SignerServer {
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        SignerServer::new(
+            local_signer(),
+            SignerPolicy {
+                attestation_grace_period: Duration::ZERO,
+                allow_early_attestation,
+            },
+            &db,
+        )
+        .unwrap()
+    }
+
+    fn request(maturation: OffsetDateTime, outcome: &str) -> SigningRequest {
+        let announcement = build_announcement(
+            &local_signer(),
+            &"BTCUSD".parse().unwrap(),
+            maturation,
+            "event".to_string(),
+            crate::EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }
+            .into(),
+        )
+        .unwrap();
+        SigningRequest {
+            asset_pair: "BTCUSD".parse().unwrap(),
+            announcement: announcement.encode(),
+            outcomes: vec![outcome.to_string()],
+            early_attestation_reason: Some("test".to_string()),
+            sk_nonces: None,
+        }
+    }
+
+    #[test]
+    fn refuses_to_reuse_nonces() {
+        let server = server(false);
+        let matured = OffsetDateTime::UNIX_EPOCH;
+        server
+            .record_event(&request(matured, "yes").announcement, None)
+            .unwrap();
+        let signatures = server.sign_outcomes(&request(matured, "yes")).unwrap();
+        assert_eq!(
+            signatures,
+            server.sign_outcomes(&request(matured, "yes")).unwrap()
+        );
+        assert!(matches!(
+            server.sign_outcomes(&request(matured, "no")),
+            Err(SignerError::NonceReuseError(_))
+        ));
+    }
+
+    #[test]
+    fn refuses_premature_attestations() {
+        let maturation = OffsetDateTime::now_utc() + Duration::DAY;
+        assert!(matches!(
+            server(false).sign_outcomes(&request(maturation, "yes")),
+            Err(SignerError::PrematureAttestationError(..))
+        ));
+        let server = server(true);
+        server
+            .record_event(&request(maturation, "yes").announcement, None)
+            .unwrap();
+        server.sign_outcomes(&request(maturation, "yes")).unwrap();
+    }
+
+    #[test]
+    fn only_attests_recorded_events_to_their_recorded_outcomes() {
+        let server = server(false);
+        let matured = OffsetDateTime::UNIX_EPOCH;
+        assert!(!server.is_seeded().unwrap());
+        assert!(matches!(
+            server.sign_outcomes(&request(matured, "yes")),
+            Err(SignerError::UnknownEventError(_))
+        ));
+
+        let attestation = build_attestation(&local_signer(), &request(matured, "yes")).unwrap();
+        server
+            .record_event(
+                &request(matured, "yes").announcement,
+                Some(&attestation.encode()),
+            )
+            .unwrap();
+        assert_eq!(0, server.seed(&[]).unwrap());
+        assert!(server.is_seeded().unwrap());
+        assert!(matches!(
+            server.sign_outcomes(&request(matured, "no")),
+            Err(SignerError::NonceReuseError(_))
+        ));
+        server.sign_outcomes(&request(matured, "yes")).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_announce_events_again_differently() {
+        let server = server(false);
+        let signer = &server.signer;
+        let btcusd = "BTCUSD".parse().unwrap();
+        let oracle_event = |maturation| OracleEvent {
+            nonces: signer.nonces(&btcusd, "event", 1).unwrap(),
+            maturation,
+            event_descriptor: crate::EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }
+            .into(),
+            event_id: "event".to_string(),
+        };
+        let maturation = OffsetDateTime::now_utc() + Duration::DAY;
+        server
+            .sign_announcement(&btcusd, &oracle_event(maturation))
+            .unwrap();
+        server
+            .sign_announcement(&btcusd, &oracle_event(maturation))
+            .unwrap();
+        assert!(matches!(
+            server.sign_announcement(&btcusd, &oracle_event(OffsetDateTime::UNIX_EPOCH)),
+            Err(SignerError::AnnouncementConflictError(_))
+        ));
+    }
+}
@@ -0,0 +1,382 @@
+use crate::{
+    oracle::{
+        announced_event_descriptor, decode_announcement, decode_attestation, OracleError, Result,
+    },
+    suredbits_encode_announcement, suredbits_encode_attestation, verify_announcement,
+    verify_attestation, AssetPair, OracleEventDescriptor,
+};
+use secp256k1_zkp::{Secp256k1, Verification};
+use serde::{Deserialize, Serialize};
+use time::OffsetDateTime;
+
+/// Version of the [`StoredEvent`] records written by this oracle.
+pub const STORED_EVENT_VERSION: u64 = 3;
+
+/// An oracle event as stored in the event database.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct StoredEvent {
+    /// version of the record, see [`STORED_EVENT_VERSION`]
+    pub version: u64,
+    pub uuid: String,
+    pub asset_pair: AssetPair,
+    pub event_descriptor: OracleEventDescriptor,
+    #[serde(with = "time::serde::rfc3339")]
+    pub maturation: OffsetDateTime,
+    /// hex-encoded public key of the oracle key which announced the event, and attests it
+    pub announcing_key: String,
+    /// time of announcement, unknown for events stored before records were versioned
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub created_at: Option<OffsetDateTime>,
+    /// secret nonces of events announced before nonces were derived, cleared once attested
+    pub outstanding_sk_nonces: Option<Vec<[u8; 32]>>,
+    pub announcement: Vec<u8>,
+    pub suredbits_announcement: Vec<u8>,
+    pub attestation: Option<Vec<u8>>,
+    pub suredbits_attestation: Option<Vec<u8>>,
+    /// attested outcome of digit decomposition events
+    pub outcome: Option<i64>,
+    /// outcome requested for attestation, if it was out of range and clamped to `outcome`
+    #[serde(default)]
+    pub unclamped_outcome: Option<i64>,
+    /// time of attestation, unknown for events attested before records were versioned
+    #[serde(with = "time::serde::rfc3339::option")]
+    pub attested_at: Option<OffsetDateTime>,
+    /// reason given for attesting before maturation, if so
+    #[serde(default)]
+    pub early_attestation_reason: Option<String>,
+    /// metadata given by the client which created the event, returned with it
+    #[serde(default)]
+    pub metadata: Option<serde_json::Map<String, serde_json::Value>>,
+    /// idempotency key of the request which created the event, if any
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+    /// idempotency key of the request which attested the event, if any
+    #[serde(default)]
+    pub attestation_idempotency_key: Option<String>,
+}
+
+// record of events stored before records were versioned:
+// outstanding_sk_nonces?, suredbits_announcement, suredbits_attestation?, announcement, attestation?, outcome?, uuid
+#[derive(Deserialize)]
+struct DbValue(
+    Option<Vec<[u8; 32]>>,
+    Vec<u8>,
+    Option<Vec<u8>>,
+    Vec<u8>,
+    Option<Vec<u8>>,
+    Option<i64>,
+    String,
+);
+
+impl StoredEvent {
+    /// Parses a record of an event of `asset_pair` read from the event database, upgrading it if
+    /// it is of an older version or if it is attested but still holds its secret nonces. Returns
+    /// the event and whether it was upgraded.
+    pub fn from_record(record: &[u8], asset_pair: &AssetPair) -> Result<(StoredEvent, bool)> {
+        let (mut event, upgraded) = StoredEvent::parse_record(record, asset_pair)?;
+        // the secret nonces of an attested event reveal the oracle key along with its signatures
+        if event.attestation.is_some() && event.outstanding_sk_nonces.is_some() {
+            event.outstanding_sk_nonces = None;
+            return Ok((event, true));
+        }
+        Ok((event, upgraded))
+    }
+
+    fn parse_record(record: &[u8], asset_pair: &AssetPair) -> Result<(StoredEvent, bool)> {
+        let record: serde_json::Value = serde_json::from_slice(record)?;
+        if record.is_array() {
+            let db_value: DbValue = serde_json::from_value(record)?;
+            let event = StoredEvent::from_db_value(db_value, asset_pair)?;
+            return Ok((event.with_suredbits_encodings()?, true));
+        }
+        let event = match record.get("version").and_then(serde_json::Value::as_u64) {
+            Some(STORED_EVENT_VERSION) => return Ok((serde_json::from_value(record)?, false)),
+            Some(2) => StoredEvent::from_v2_record(record)?,
+            Some(1) => StoredEvent::from_v1_record(record)?,
+            version => {
+                return Err(OracleError::UnsupportedEventVersionError(
+                    version.map_or("none".to_string(), |version| version.to_string()),
+                ))
+            }
+        };
+        Ok((event.with_suredbits_encodings()?, true))
+    }
+
+    /// Serializes this event into a record for the event database.
+    pub fn to_record(&self) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(self)?)
+    }
+
+    /// Verifies the announcement of this event and its attestation, if attested, see
+    /// [`verify_announcement`] and [`verify_attestation`], and that the event matches them.
+    pub fn verify<C: Verification>(&self, secp: &Secp256k1<C>) -> Result<()> {
+        let inconsistent = |reason: &str| {
+            OracleError::InconsistentEventError(self.uuid.clone(), reason.to_string())
+        };
+        let announcement = decode_announcement(&self.announcement)?;
+        verify_announcement(secp, &announcement)?;
+        if announcement.oracle_public_key.to_string() != self.announcing_key {
+            return Err(inconsistent(
+                "announcing key differs from the announcement's",
+            ));
+        }
+        if i64::from(announcement.oracle_event.event_maturity_epoch)
+            != self.maturation.unix_timestamp()
+        {
+            return Err(inconsistent("maturation differs from the announcement's"));
+        }
+        if announced_event_descriptor(&announcement) != self.event_descriptor {
+            return Err(inconsistent(
+                "event descriptor differs from the announcement's",
+            ));
+        }
+        if suredbits_encode_announcement(&announcement) != self.suredbits_announcement {
+            return Err(inconsistent(
+                "Suredbits announcement differs from the announcement",
+            ));
+        }
+        match (&self.attestation, &self.suredbits_attestation) {
+            (Some(attestation), Some(suredbits_attestation)) => {
+                let attestation = decode_attestation(attestation)?;
+                verify_attestation(secp, &announcement, &attestation)?;
+                if &suredbits_encode_attestation(&announcement.oracle_event.event_id, &attestation)
+                    != suredbits_attestation
+                {
+                    return Err(inconsistent(
+                        "Suredbits attestation differs from the attestation",
+                    ));
+                }
+            }
+            (None, None) => {}
+            _ => return Err(inconsistent("only one of its attestations is stored")),
+        }
+        Ok(())
+    }
+
+    // records of version 2 only differ in their Suredbits encodings, which are encoded again
+    fn from_v2_record(mut record: serde_json::Value) -> Result<StoredEvent> {
+        record["version"] = STORED_EVENT_VERSION.into();
+        Ok(serde_json::from_value(record)?)
+    }
+
+    // records of version 1 do not record the announcing key, which is that of their announcement
+    fn from_v1_record(mut record: serde_json::Value) -> Result<StoredEvent> {
+        let announcement: Vec<u8> = serde_json::from_value(record["announcement"].take())?;
+        record["announcing_key"] = decode_announcement(&announcement)?
+            .oracle_public_key
+            .to_string()
+            .into();
+        record["announcement"] = announcement.into();
+        record["version"] = STORED_EVENT_VERSION.into();
+        Ok(serde_json::from_value(record)?)
+    }
+
+    // Suredbits attestations written before version 3 lack their event id
+    fn with_suredbits_encodings(mut self) -> Result<StoredEvent> {
+        let announcement = decode_announcement(&self.announcement)?;
+        self.suredbits_announcement = suredbits_encode_announcement(&announcement);
+        if let Some(attestation) = &self.attestation {
+            self.suredbits_attestation = Some(suredbits_encode_attestation(
+                &announcement.oracle_event.event_id,
+                &decode_attestation(attestation)?,
+            ));
+        }
+        Ok(self)
+    }
+
+    fn from_db_value(db_value: DbValue, asset_pair: &AssetPair) -> Result<StoredEvent> {
+        let announcement = decode_announcement(&db_value.3)?;
+        let maturation = OffsetDateTime::from_unix_timestamp(
+            announcement.oracle_event.event_maturity_epoch.into(),
+        )
+        .map_err(|e| OracleError::AnnouncementDecodeError(e.to_string()))?;
+        Ok(StoredEvent {
+            version: STORED_EVENT_VERSION,
+            uuid: db_value.6,
+            asset_pair: asset_pair.clone(),
+            event_descriptor: announced_event_descriptor(&announcement),
+            maturation,
+            announcing_key: announcement.oracle_public_key.to_string(),
+            created_at: None,
+            outstanding_sk_nonces: db_value.0,
+            announcement: db_value.3,
+            suredbits_announcement: db_value.1,
+            attestation: db_value.4,
+            suredbits_attestation: db_value.2,
+            outcome: db_value.5,
+            unclamped_outcome: None,
+            attested_at: None,
+            early_attestation_reason: None,
+            metadata: None,
+            idempotency_key: None,
+            attestation_idempotency_key: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        oracle::{
+            oracle_scheduler::{build_announcement, build_attestation},
+            signer::SigningRequest,
+            test_utils::{keypair, local_signer},
+        },
+        Attestation, EventDescriptor,
+    };
+    use secp256k1_zkp::Secp256k1;
+
+    fn legacy_record(attested: bool) -> (Vec<u8>, OffsetDateTime) {
+        let maturation = OffsetDateTime::from_unix_timestamp(1653984000).unwrap();
+        let signer = local_signer();
+        let btcusd = "BTCUSD".parse().unwrap();
+        let announcement = build_announcement(
+            &signer,
+            &btcusd,
+            maturation,
+            "event".to_string(),
+            EventDescriptor {
+                base: 2,
+                is_signed: false,
+                unit: "BTCUSD".to_string(),
+                precision: 0,
+                num_digits: 4,
+            }
+            .into(),
+        )
+        .unwrap();
+        let attestation = attested.then(|| {
+            build_attestation(
+                &signer,
+                &SigningRequest {
+                    asset_pair: btcusd,
+                    announcement: announcement.encode(),
+                    outcomes: vec!["0".to_string(); 4],
+                    early_attestation_reason: None,
+                    sk_nonces: None,
+                },
+            )
+            .unwrap()
+        });
+        let sk_nonces = vec![[7u8; 32]; 4];
+        let record = serde_json::to_vec(&serde_json::json!([
+            sk_nonces,
+            announcement.suredbits_encode(),
+            attestation.as_ref().map(Attestation::suredbits_encode),
+            announcement.encode(),
+            attestation.as_ref().map(Attestation::encode),
+            attested.then_some(0),
+            "event",
+        ]))
+        .unwrap();
+        (record, maturation)
+    }
+
+    #[test]
+    fn upgrades_unversioned_records() {
+        let (record, maturation) = legacy_record(false);
+        let (event, upgraded) =
+            StoredEvent::from_record(&record, &"BTCUSD".parse().unwrap()).unwrap();
+        assert!(upgraded);
+        assert_eq!(STORED_EVENT_VERSION, event.version);
+        assert_eq!("event", event.uuid);
+        assert_eq!(maturation, event.maturation);
+        assert_eq!(4, event.event_descriptor.nb_nonces());
+        assert_eq!(Some(vec![[7u8; 32]; 4]), event.outstanding_sk_nonces);
+        assert_eq!(None, event.created_at);
+        assert_eq!(
+            keypair(&Secp256k1::new()).public_key().to_string(),
+            event.announcing_key
+        );
+    }
+
+    #[test]
+    fn clears_nonces_of_attested_records() {
+        let (record, _) = legacy_record(true);
+        let asset_pair = "BTCUSD".parse().unwrap();
+        let (event, upgraded) = StoredEvent::from_record(&record, &asset_pair).unwrap();
+        assert!(upgraded);
+        assert!(event.attestation.is_some());
+        assert_eq!(None, event.outstanding_sk_nonces);
+        event.verify(&Secp256k1::verification_only()).unwrap();
+
+        let mut current_record = serde_json::to_value(&event).unwrap();
+        current_record["outstanding_sk_nonces"] = serde_json::json!(vec![[7u8; 32]; 4]);
+        let (read, upgraded) =
+            StoredEvent::from_record(&serde_json::to_vec(&current_record).unwrap(), &asset_pair)
+                .unwrap();
+        assert!(upgraded);
+        assert_eq!(None, read.outstanding_sk_nonces);
+    }
+
+    #[test]
+    fn upgrades_v1_records() {
+        let (record, _) = legacy_record(false);
+        let asset_pair = "BTCUSD".parse().unwrap();
+        let (event, _) = StoredEvent::from_record(&record, &asset_pair).unwrap();
+        let mut v1_record = serde_json::to_value(&event).unwrap();
+        v1_record["version"] = 1.into();
+        v1_record.as_object_mut().unwrap().remove("announcing_key");
+
+        let (read, upgraded) =
+            StoredEvent::from_record(&serde_json::to_vec(&v1_record).unwrap(), &asset_pair)
+                .unwrap();
+        assert!(upgraded);
+        assert_eq!(STORED_EVENT_VERSION, read.version);
+        assert_eq!(event.announcing_key, read.announcing_key);
+        assert_eq!(event.announcement, read.announcement);
+    }
+
+    #[test]
+    fn upgrades_v2_records() {
+        let (record, _) = legacy_record(false);
+        let asset_pair = "BTCUSD".parse().unwrap();
+        let (event, _) = StoredEvent::from_record(&record, &asset_pair).unwrap();
+        let mut v2_record = serde_json::to_value(&event).unwrap();
+        v2_record["version"] = 2.into();
+        v2_record["suredbits_announcement"] = serde_json::json!([]);
+
+        let (read, upgraded) =
+            StoredEvent::from_record(&serde_json::to_vec(&v2_record).unwrap(), &asset_pair)
+                .unwrap();
+        assert!(upgraded);
+        assert_eq!(STORED_EVENT_VERSION, read.version);
+        assert_eq!(event.suredbits_announcement, read.suredbits_announcement);
+        assert!(!read.suredbits_announcement.is_empty());
+    }
+
+    #[test]
+    fn reads_current_records() {
+        let (record, _) = legacy_record(false);
+        let asset_pair = "BTCUSD".parse().unwrap();
+        let (event, _) = StoredEvent::from_record(&record, &asset_pair).unwrap();
+        let (read, upgraded) =
+            StoredEvent::from_record(&event.to_record().unwrap(), &asset_pair).unwrap();
+        assert!(!upgraded);
+        assert_eq!(event.announcement, read.announcement);
+        assert_eq!(event.event_descriptor, read.event_descriptor);
+    }
+
+    #[test]
+    fn verifies_events() {
+        let (record, maturation) = legacy_record(false);
+        let (mut event, _) = StoredEvent::from_record(&record, &"BTCUSD".parse().unwrap()).unwrap();
+        let secp = Secp256k1::verification_only();
+        event.verify(&secp).unwrap();
+
+        event.maturation = maturation + time::Duration::DAY;
+        assert!(matches!(
+            event.verify(&secp),
+            Err(OracleError::InconsistentEventError(..))
+        ));
+    }
+
+    #[test]
+    fn rejects_unknown_versions() {
+        assert!(matches!(
+            StoredEvent::from_record(br#"{"version":99}"#, &"BTCUSD".parse().unwrap()),
+            Err(OracleError::UnsupportedEventVersionError(_))
+        ));
+    }
+}
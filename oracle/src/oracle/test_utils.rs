@@ -0,0 +1,58 @@
+//! Fixtures shared by the tests of the oracle modules.
+
+use crate::{
+    oracle::{signer::LocalSigner, EventHandler, Keyring, Oracle},
+    AssetPairInfo, OracleConfig,
+};
+use secp256k1_zkp::{All, KeyPair, Secp256k1};
+use std::sync::Arc;
+
+/// Returns the oracle key of the tests.
+pub fn keypair(secp: &Secp256k1<All>) -> KeyPair {
+    KeyPair::from_seckey_slice(secp, &[1u8; 32]).unwrap()
+}
+
+/// Returns a signer holding the oracle key of the tests in memory.
+pub fn local_signer() -> LocalSigner {
+    let secp = Secp256k1::new();
+    LocalSigner::new(Keyring::new(keypair(&secp), vec![]), secp)
+}
+
+/// Returns the config of an oracle announcing events a day before they mature, daily at 08:00.
+pub fn oracle_config() -> OracleConfig {
+    serde_json::from_value(serde_json::json!({
+        "attestation_time": "08:00",
+        "frequency": "1d",
+        "announcement_offset": "1d",
+    }))
+    .unwrap()
+}
+
+/// Returns BTCUSD, announced with 8 unsigned binary digits, without pricefeeds.
+pub fn asset_pair_info() -> AssetPairInfo {
+    serde_json::from_value(serde_json::json!({
+        "asset_pair": "BTCUSD",
+        "event_descriptor": {
+            "base": 2,
+            "is_signed": false,
+            "unit": "BTCUSD",
+            "precision": 0,
+            "num_digits": 8,
+        },
+    }))
+    .unwrap()
+}
+
+/// Returns the oracle of `asset_pair_info`, configured by [`oracle_config`], which stores its
+/// events in memory and signs with [`local_signer`].
+pub fn oracle(asset_pair_info: AssetPairInfo) -> Oracle {
+    let asset_pair = asset_pair_info.asset_pair.clone();
+    Oracle::new(
+        oracle_config(),
+        asset_pair_info,
+        EventHandler::memory().for_asset_pair(&asset_pair).unwrap(),
+        Arc::new(local_signer()),
+        Secp256k1::new(),
+    )
+    .unwrap()
+}
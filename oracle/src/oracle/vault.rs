@@ -1,3 +1,5 @@
+use crate::auth::ApiKeyConfig;
+use crate::oracle::keyring::RetiredKey;
 use serde::{Deserialize, Serialize};
 use std::env;
 use vaultrs::api::kv2::responses::SecretVersionMetadata;
@@ -48,6 +50,26 @@ pub struct OraclePrivateKey {
     pub private_key_value: String,
 }
 
+#[derive(Deserialize, Serialize, Clone)]
+/// This struct represents the Oracle private keys retired by key rotations.
+///
+/// It contains one field:
+///
+/// - `keys`: The retired keys, with the time they were retired.
+pub struct RetiredOracleKeys {
+    pub keys: Vec<RetiredKey>,
+}
+
+#[derive(Deserialize, Serialize, Clone)]
+/// This struct represents the API keys allowed to create and attest events.
+///
+/// It contains one field:
+///
+/// - `keys`: The API keys, see `ApiKeyConfig`.
+pub struct ApiKeysSecret {
+    pub keys: Vec<ApiKeyConfig>,
+}
+
 /// Retrieves an Oracle private key from the Vault server.
 ///
 /// # Arguments
@@ -86,3 +108,57 @@ pub async fn set_secret_key(
         kv2::set(&client, secret_mount, secret_path, &value.clone()).await?;
     Ok(value.clone())
 }
+
+/// Retrieves the retired Oracle private keys from the Vault server.
+///
+/// # Arguments
+///
+/// * `secret_path` - The path to the secret in the Vault server.
+///
+/// # Returns
+///
+/// * `Result<RetiredOracleKeys, ClientError>` - The retired keys, or an error if one occurred.
+pub async fn get_retired_keys(
+    secret_path: &str,
+    secret_mount: &str,
+) -> Result<RetiredOracleKeys, ClientError> {
+    let client = VaultConn::new().client;
+    kv2::read(&client, secret_mount, secret_path).await
+}
+
+/// Sets the retired Oracle private keys in the Vault server.
+///
+/// # Arguments
+///
+/// * `secret_path` - The path to the secret in the Vault server.
+/// * `value` - The retired keys.
+///
+/// # Returns
+///
+/// * `Result<(), ClientError>` - An error if one occurred.
+pub async fn set_retired_keys(
+    secret_path: &str,
+    secret_mount: &str,
+    value: &RetiredOracleKeys,
+) -> Result<(), ClientError> {
+    let client = VaultConn::new().client;
+    let _: SecretVersionMetadata = kv2::set(&client, secret_mount, secret_path, value).await?;
+    Ok(())
+}
+
+/// Retrieves the API keys from the Vault server.
+///
+/// # Arguments
+///
+/// * `secret_path` - The path to the secret in the Vault server.
+///
+/// # Returns
+///
+/// * `Result<ApiKeysSecret, ClientError>` - The API keys, or an error if one occurred.
+pub async fn get_api_keys(
+    secret_path: &str,
+    secret_mount: &str,
+) -> Result<ApiKeysSecret, ClientError> {
+    let client = VaultConn::new().client;
+    kv2::read(&client, secret_mount, secret_path).await
+}
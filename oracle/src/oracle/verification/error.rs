@@ -0,0 +1,34 @@
+use displaydoc::Display;
+use thiserror::Error;
+
+pub type Result<T> = std::result::Result<T, VerificationError>;
+
+#[derive(Debug, Display, Error)]
+pub enum VerificationError {
+    /// malformed key or signature: {0}
+    Secp256k1UpstreamError(#[from] secp256k1_zkp::UpstreamError),
+
+    /// announcement signature does not match the oracle public key
+    InvalidAnnouncementSignatureError,
+
+    /// event descriptor requires {0} nonces but the announcement has {1}
+    NonceCountMismatchError(usize, usize),
+
+    /// announcement reuses nonce {0}
+    DuplicateNonceError(usize),
+
+    /// attestation is signed by another oracle than the announcement
+    OraclePublicKeyMismatchError,
+
+    /// announcement has {0} nonces but the attestation has {1} outcomes and {2} signatures
+    OutcomeCountMismatchError(usize, usize, usize),
+
+    /// outcome {1} at position {0} is not a valid outcome of the event
+    InvalidOutcomeError(usize, String),
+
+    /// signature {0} does not use the announced nonce
+    SignatureNonceMismatchError(usize),
+
+    /// signature {0} does not match its outcome
+    InvalidAttestationSignatureError(usize),
+}
@@ -0,0 +1,200 @@
+use crate::{oracle::oracle_queryable::messaging::OracleAnnouncementHash, OracleEventDescriptor};
+use dlc_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use lightning::util::ser::Writeable;
+use secp256k1_zkp::{
+    hashes::sha256, schnorr::Signature as SchnorrSignature, Message, Secp256k1, Verification,
+    XOnlyPublicKey as SchnorrPublicKey,
+};
+
+mod error;
+pub use error::Result;
+pub use error::VerificationError;
+
+/// Verifies that `announcement` is signed by its oracle public key under the
+/// `DLC/oracle/announcement/v0` tagged hash, and that it has one distinct nonce per outcome to be
+/// attested, as required by its event descriptor.
+pub fn verify_announcement<C: Verification>(
+    secp: &Secp256k1<C>,
+    announcement: &OracleAnnouncement,
+) -> Result<()> {
+    let oracle_pubkey = SchnorrPublicKey::from_slice(&announcement.oracle_public_key.serialize())?;
+    let signature = SchnorrSignature::from_slice(announcement.announcement_signature.as_ref())?;
+    let message =
+        Message::from_hashed_data::<OracleAnnouncementHash>(&announcement.oracle_event.encode());
+    secp.verify_schnorr(&signature, &message, &oracle_pubkey)
+        .map_err(|_| VerificationError::InvalidAnnouncementSignatureError)?;
+
+    let nonces = &announcement.oracle_event.oracle_nonces;
+    let nb_nonces =
+        OracleEventDescriptor::from(&announcement.oracle_event.event_descriptor).nb_nonces();
+    if nonces.len() != nb_nonces {
+        return Err(VerificationError::NonceCountMismatchError(
+            nb_nonces,
+            nonces.len(),
+        ));
+    }
+    // signing two outcomes with the same nonce would leak the oracle secret key
+    for (index, nonce) in nonces.iter().enumerate() {
+        if nonces[..index].contains(nonce) {
+            return Err(VerificationError::DuplicateNonceError(index));
+        }
+    }
+    Ok(())
+}
+
+/// Verifies that `attestation` attests to valid outcomes of the event of `announcement`, each
+/// signed by the oracle with the nonce announced for it. `announcement` is verified first, see
+/// [`verify_announcement`].
+pub fn verify_attestation<C: Verification>(
+    secp: &Secp256k1<C>,
+    announcement: &OracleAnnouncement,
+    attestation: &OracleAttestation,
+) -> Result<()> {
+    verify_announcement(secp, announcement)?;
+
+    if attestation.oracle_public_key != announcement.oracle_public_key {
+        return Err(VerificationError::OraclePublicKeyMismatchError);
+    }
+    let nonces = &announcement.oracle_event.oracle_nonces;
+    if attestation.outcomes.len() != nonces.len() || attestation.signatures.len() != nonces.len() {
+        return Err(VerificationError::OutcomeCountMismatchError(
+            nonces.len(),
+            attestation.outcomes.len(),
+            attestation.signatures.len(),
+        ));
+    }
+
+    let event_descriptor = OracleEventDescriptor::from(&announcement.oracle_event.event_descriptor);
+    let oracle_pubkey = SchnorrPublicKey::from_slice(&attestation.oracle_public_key.serialize())?;
+    for (index, ((outcome, signature), nonce)) in attestation
+        .outcomes
+        .iter()
+        .zip(&attestation.signatures)
+        .zip(nonces)
+        .enumerate()
+    {
+        if !is_valid_outcome(&event_descriptor, index, outcome) {
+            return Err(VerificationError::InvalidOutcomeError(
+                index,
+                outcome.clone(),
+            ));
+        }
+        // the first half of a schnorr signature is its public nonce
+        let signature = SchnorrSignature::from_slice(signature.as_ref())?;
+        if signature.as_ref()[..32] != nonce.serialize()[..] {
+            return Err(VerificationError::SignatureNonceMismatchError(index));
+        }
+        secp.verify_schnorr(
+            &signature,
+            &Message::from_hashed_data::<sha256::Hash>(outcome.as_bytes()),
+            &oracle_pubkey,
+        )
+        .map_err(|_| VerificationError::InvalidAttestationSignatureError(index))?;
+    }
+    Ok(())
+}
+
+/// Returns whether `outcome` may be attested with the nonce at `index` of an event described by
+/// `event_descriptor`.
+fn is_valid_outcome(event_descriptor: &OracleEventDescriptor, index: usize, outcome: &str) -> bool {
+    match event_descriptor {
+        OracleEventDescriptor::DigitDecompositionEvent(event_descriptor) => {
+            if event_descriptor.is_signed && index == 0 {
+                return outcome == "+" || outcome == "-";
+            }
+            // digits are attested in canonical decimal form
+            outcome.parse::<u16>().map_or(false, |digit| {
+                digit < event_descriptor.base && digit.to_string() == outcome
+            })
+        }
+        OracleEventDescriptor::EnumEvent(event_descriptor) => {
+            event_descriptor.outcomes.iter().any(|o| o == outcome)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        oracle::{
+            oracle_scheduler::{build_announcement, build_attestation},
+            signer::SigningRequest,
+            test_utils::local_signer,
+        },
+        AssetPair, EventDescriptor,
+    };
+    use time::OffsetDateTime;
+
+    fn event_descriptor() -> OracleEventDescriptor {
+        EventDescriptor {
+            base: 2,
+            is_signed: true,
+            unit: "BTCUSD".to_string(),
+            precision: 0,
+            num_digits: 4,
+        }
+        .into()
+    }
+
+    fn announce_and_attest(outcomes: &[&str]) -> (OracleAnnouncement, OracleAttestation) {
+        let btcusd: AssetPair = "BTCUSD".parse().unwrap();
+        let signer = local_signer();
+        let announcement = build_announcement(
+            &signer,
+            &btcusd,
+            OffsetDateTime::UNIX_EPOCH,
+            "event".to_string(),
+            event_descriptor(),
+        )
+        .unwrap();
+        let attestation = build_attestation(
+            &signer,
+            &SigningRequest {
+                asset_pair: btcusd,
+                announcement: announcement.encode(),
+                outcomes: outcomes.iter().map(ToString::to_string).collect(),
+                early_attestation_reason: None,
+                sk_nonces: None,
+            },
+        )
+        .unwrap();
+        ((&announcement).into(), (&attestation).into())
+    }
+
+    #[test]
+    fn verifies_built_announcement_and_attestation() {
+        let secp = Secp256k1::new();
+        let (announcement, attestation) = announce_and_attest(&["-", "0", "1", "0", "1"]);
+        verify_announcement(&secp, &announcement).unwrap();
+        verify_attestation(&secp, &announcement, &attestation).unwrap();
+    }
+
+    #[test]
+    fn rejects_tampered_announcement() {
+        let secp = Secp256k1::new();
+        let (mut announcement, _) = announce_and_attest(&["+", "0", "0", "0", "0"]);
+        announcement.oracle_event.event_id = "other".to_string();
+        assert!(matches!(
+            verify_announcement(&secp, &announcement),
+            Err(VerificationError::InvalidAnnouncementSignatureError)
+        ));
+    }
+
+    #[test]
+    fn rejects_tampered_attestation() {
+        let secp = Secp256k1::new();
+        let (announcement, mut attestation) = announce_and_attest(&["+", "0", "1", "0", "1"]);
+        attestation.outcomes[4] = "0".to_string();
+        assert!(matches!(
+            verify_attestation(&secp, &announcement, &attestation),
+            Err(VerificationError::InvalidAttestationSignatureError(4))
+        ));
+
+        let (announcement, attestation) = announce_and_attest(&["+", "0", "1", "0", "2"]);
+        assert!(matches!(
+            verify_attestation(&secp, &announcement, &attestation),
+            Err(VerificationError::InvalidOutcomeError(4, _))
+        ));
+    }
+}
@@ -1,17 +1,57 @@
 use crate::DbPool;
-use actix_web::web::{Data, Json, Path};
+use actix_web::web::{Data, Json, Path, Query};
 use actix_web::{delete, get, post, put, HttpResponse, Responder};
 use dlc_storage_common::models::{NewEvent, UpdateEvent};
 use dlc_storage_reader;
 use dlc_storage_writer;
+use serde::Deserialize;
+
+/// Query of a page of events, all events being returned without `limit`.
+#[derive(Deserialize)]
+pub struct EventsQuery {
+    /// only events whose event id starts with `prefix`
+    #[serde(default)]
+    prefix: String,
+    /// only events after this event id, or before it if `reverse` is set
+    cursor: Option<String>,
+    limit: Option<i64>,
+    #[serde(default)]
+    reverse: bool,
+}
 
 #[get("/events")]
-pub async fn get_events(pool: Data<DbPool>) -> impl Responder {
+pub async fn get_events(pool: Data<DbPool>, query: Query<EventsQuery>) -> impl Responder {
     let mut conn = pool.get().expect("couldn't get db connection from pool");
-    let events = dlc_storage_reader::get_events(&mut conn).unwrap();
+    let query = query.into_inner();
+    let events = match query.limit {
+        Some(limit) => dlc_storage_reader::get_events_page(
+            &mut conn,
+            &query.prefix,
+            query.cursor,
+            limit,
+            query.reverse,
+        )
+        .unwrap(),
+        None => dlc_storage_reader::get_events(&mut conn).unwrap(),
+    };
     HttpResponse::Ok().json(events)
 }
 
+/// Query of the number of events.
+#[derive(Deserialize)]
+pub struct CountQuery {
+    /// only events whose event id starts with `prefix`
+    #[serde(default)]
+    prefix: String,
+}
+
+#[get("/events/count")]
+pub async fn count_events(pool: Data<DbPool>, query: Query<CountQuery>) -> impl Responder {
+    let mut conn = pool.get().expect("couldn't get db connection from pool");
+    let count = dlc_storage_reader::count_events(&mut conn, &query.prefix).unwrap();
+    HttpResponse::Ok().json(count)
+}
+
 #[get("/events/{uuid}")]
 pub async fn get_event(pool: Data<DbPool>, uuid: Path<String>) -> impl Responder {
     let mut conn = pool.get().expect("couldn't get db connection from pool");
@@ -30,6 +70,13 @@ pub async fn create_event(pool: Data<DbPool>, event: Json<NewEvent>) -> impl Res
     HttpResponse::Ok().json(events)
 }
 
+#[put("/events")]
+pub async fn upsert_events(pool: Data<DbPool>, events: Json<Vec<NewEvent>>) -> impl Responder {
+    let mut conn = pool.get().expect("couldn't get db connection from pool");
+    let events = dlc_storage_writer::upsert_events(&mut conn, events.into_inner()).unwrap();
+    HttpResponse::Ok().json(events)
+}
+
 #[put("/events/{uuid}")]
 pub async fn update_event(
     pool: Data<DbPool>,
@@ -53,8 +53,11 @@ async fn main() -> std::io::Result<()> {
             .service(delete_contract)
             .service(delete_contracts)
             .service(get_events)
+            // before get_event, which would take "count" for a uuid
+            .service(count_events)
             .service(get_event)
             .service(create_event)
+            .service(upsert_events)
             .service(update_event)
             .service(delete_event)
             .service(delete_events)
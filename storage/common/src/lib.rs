@@ -2,7 +2,9 @@ pub mod models;
 pub mod schema;
 
 use crate::models::*;
-use diesel::expression_methods::ExpressionMethods;
+use diesel::expression_methods::{
+    EscapeExpressionMethods, ExpressionMethods, TextExpressionMethods,
+};
 use diesel::query_dsl::QueryDsl;
 use diesel::RunQueryDsl;
 use diesel::{r2d2::Error, PgConnection};
@@ -90,6 +92,21 @@ pub fn create_event(
     Ok(result)
 }
 
+pub fn upsert_events(
+    conn: &mut PgConnection,
+    new_events: Vec<NewEvent>,
+) -> Result<Vec<Event>, diesel::result::Error> {
+    use crate::schema::events::dsl::*;
+    use diesel::upsert::excluded;
+    let results = diesel::insert_into(events)
+        .values(&new_events)
+        .on_conflict(event_id)
+        .do_update()
+        .set(content.eq(excluded(content)))
+        .get_results(conn)?;
+    Ok(results)
+}
+
 pub fn update_event(
     conn: &mut PgConnection,
     eid: &str,
@@ -114,6 +131,86 @@ pub fn get_all_events(conn: &mut PgConnection) -> Result<Vec<Event>, diesel::res
     Ok(results)
 }
 
+// returns the LIKE pattern of the strings starting with `prefix`, escaped with backslashes
+fn prefix_pattern(prefix: &str) -> String {
+    format!(
+        "{}%",
+        prefix
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_")
+    )
+}
+
+/// Returns the events whose event id starts with `prefix`.
+pub fn get_events_with_prefix(
+    conn: &mut PgConnection,
+    prefix: &str,
+) -> Result<Vec<Event>, diesel::result::Error> {
+    use crate::schema::events::dsl::*;
+    let results = events
+        .filter(event_id.like(prefix_pattern(prefix)).escape('\\'))
+        .load::<Event>(conn)?;
+    Ok(results)
+}
+
+/// Returns the number of events whose event id starts with `prefix`.
+pub fn count_events_with_prefix(
+    conn: &mut PgConnection,
+    prefix: &str,
+) -> Result<i64, diesel::result::Error> {
+    use crate::schema::events::dsl::*;
+    let result = events
+        .filter(event_id.like(prefix_pattern(prefix)).escape('\\'))
+        .count()
+        .get_result(conn)?;
+    Ok(result)
+}
+
+/// Returns at most `limit` of the events whose event id starts with `prefix`, in order of event
+/// id: the first ones after `cursor`, or the last ones before it in reverse order if `reverse` is
+/// set.
+pub fn get_events_page(
+    conn: &mut PgConnection,
+    prefix: &str,
+    cursor: Option<String>,
+    limit: i64,
+    reverse: bool,
+) -> Result<Vec<Event>, diesel::result::Error> {
+    use crate::schema::events::dsl::*;
+    let mut query = events
+        .filter(event_id.like(prefix_pattern(prefix)).escape('\\'))
+        .into_boxed();
+    query = match (cursor, reverse) {
+        (Some(cursor), false) => query.filter(event_id.gt(cursor)),
+        (Some(cursor), true) => query.filter(event_id.lt(cursor)),
+        (None, _) => query,
+    };
+    query = if reverse {
+        query.order(event_id.desc())
+    } else {
+        query.order(event_id.asc())
+    };
+    let results = query.limit(limit).load::<Event>(conn)?;
+    Ok(results)
+}
+
+/// Returns the events of `eids` which exist, locking them until the end of the current
+/// transaction, so that concurrent transactions updating them wait for it.
+pub fn lock_events(
+    conn: &mut PgConnection,
+    eids: &[String],
+) -> Result<Vec<Event>, diesel::result::Error> {
+    use crate::schema::events::dsl::*;
+    // rows are locked in a consistent order, so that concurrent transactions cannot deadlock
+    let results = events
+        .filter(event_id.eq_any(eids))
+        .order(event_id)
+        .for_update()
+        .load::<Event>(conn)?;
+    Ok(results)
+}
+
 pub fn delete_event(conn: &mut PgConnection, eid: &str) -> Result<usize, diesel::result::Error> {
     use crate::schema::events::dsl::*;
     let num_deleted = diesel::delete(events.filter(event_id.eq(eid))).execute(conn)?;
@@ -25,6 +25,20 @@ pub fn get_events(conn: &mut PgConnection) -> Result<Vec<Event>, diesel::result:
     return dlc_storage_common::get_all_events(conn);
 }
 
+pub fn get_events_page(
+    conn: &mut PgConnection,
+    prefix: &str,
+    cursor: Option<String>,
+    limit: i64,
+    reverse: bool,
+) -> Result<Vec<Event>, diesel::result::Error> {
+    return dlc_storage_common::get_events_page(conn, prefix, cursor, limit, reverse);
+}
+
+pub fn count_events(conn: &mut PgConnection, prefix: &str) -> Result<i64, diesel::result::Error> {
+    return dlc_storage_common::count_events_with_prefix(conn, prefix);
+}
+
 pub fn get_event(conn: &mut PgConnection, cuuid: &str) -> Result<Event, diesel::result::Error> {
     return dlc_storage_common::get_event(conn, cuuid);
 }
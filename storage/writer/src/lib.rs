@@ -41,6 +41,13 @@ pub fn create_event(
     return dlc_storage_common::create_event(conn, event);
 }
 
+pub fn upsert_events(
+    conn: &mut PgConnection,
+    events: Vec<NewEvent>,
+) -> Result<Vec<Event>, diesel::result::Error> {
+    return dlc_storage_common::upsert_events(conn, events);
+}
+
 pub fn update_event(
     conn: &mut PgConnection,
     eid: &str,
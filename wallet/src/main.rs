@@ -89,8 +89,13 @@ fn main() {
     let auth = Auth::UserPass(rpc_user, rpc_pass);
     let rpc = Client::new(&format!("http://{}", btc_rpc_url), auth.clone()).unwrap();
     let bitcoin_core = Arc::new(BitcoinCoreProvider { client: rpc });
+    // the oracle public key may be pinned, in which case other oracles are rejected
+    let oracle_public_key: Option<secp256k1_zkp::schnorrsig::PublicKey> =
+        env::var("ORACLE_PUBLIC_KEY")
+            .ok()
+            .map(|public_key| public_key.parse().expect("invalid ORACLE_PUBLIC_KEY"));
     let p2p_client: P2PDOracleClient = retry!(
-        P2PDOracleClient::new(&oracle_url),
+        P2PDOracleClient::new(&oracle_url, oracle_public_key),
         10,
         "oracle client creation"
     );
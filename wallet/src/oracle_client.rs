@@ -28,18 +28,28 @@ use std::str::FromStr;
 // use chrono::{DateTime, NaiveDateTime, Utc};
 use dlc_manager::error::Error as DlcManagerError;
 use dlc_manager::Oracle;
-use dlc_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation};
+use dlc_messages::oracle_msgs::{OracleAnnouncement, OracleAttestation, OracleEvent};
 use log::info;
 
+use dlc_messages::oracle_msgs::EventDescriptor;
+use lightning::util::ser::Writeable;
+use secp256k1_zkp::hashes::{sha256, Hash, HashEngine};
 use secp256k1_zkp::schnorrsig::PublicKey;
 use secp256k1_zkp::schnorrsig::Signature;
+use secp256k1_zkp::{Message, Secp256k1, Verification, VerifyOnly};
+
+// tag of the hash announcements are signed with, see the DLC spec
+const ANNOUNCEMENT_TAG: &[u8] = b"DLC/oracle/announcement/v0";
 
 /// Enables interacting with a DLC oracle.
+///
+/// Announcements and attestations are only returned once verified to be signed by the oracle
+/// public key, with the nonces and outcomes their event descriptor requires.
 pub struct P2PDOracleClient {
     host: String,
     public_key: PublicKey,
+    secp: Secp256k1<VerifyOnly>,
     // key_pair: KeyPair,
-    // secp: Secp256k1<All>,
     // announcements: Mutex<HashMap<String, OracleAnnouncement>>,
     // attestations: Mutex<HashMap<String, OracleAttestation>>,
     // nonces: Mutex<HashMap<String, Vec<SecretKey>>>,
@@ -147,11 +157,265 @@ fn attestation_path(host: &str, event_id: &str) -> String {
 //     }
 // }
 
+/// Errors of oracles which cannot be trusted, or reached.
+#[derive(Debug)]
+pub enum OracleClientError {
+    /// The oracle could not be reached or returned an unexpected response.
+    Http(String),
+    /// A field is missing from the oracle response.
+    MissingField(&'static str),
+    /// A message is not valid hex.
+    HexDecode(DecodeHexError),
+    /// A message is not a valid TLV.
+    MessageDecode(String),
+    /// The oracle public key is malformed.
+    InvalidPublicKey(String),
+    /// The oracle public key is not the pinned one.
+    PublicKeyMismatch {
+        /// pinned public key
+        expected: PublicKey,
+        /// public key of the oracle
+        actual: PublicKey,
+    },
+    /// The oracle returned another event than the requested one.
+    EventIdMismatch {
+        /// requested event id
+        expected: String,
+        /// event id of the returned announcement
+        actual: String,
+    },
+    /// The announcement is not signed by the oracle.
+    InvalidAnnouncementSignature,
+    /// The announcement does not have as many nonces as its event descriptor requires.
+    NonceCountMismatch {
+        /// number of nonces required by the event descriptor
+        expected: usize,
+        /// number of announced nonces
+        actual: usize,
+    },
+    /// The announcement reuses the nonce at this index.
+    DuplicateNonce(usize),
+    /// The event is not attested yet.
+    MissingAttestation(String),
+    /// The attestation does not have one outcome and signature per announced nonce.
+    OutcomeCountMismatch,
+    /// The outcome at this index is not a valid outcome of the event.
+    InvalidOutcome(usize, String),
+    /// The signature at this index does not use the announced nonce.
+    SignatureNonceMismatch(usize),
+    /// The signature at this index does not match its outcome.
+    InvalidAttestationSignature(usize),
+}
+
+impl fmt::Display for OracleClientError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OracleClientError::Http(e) => write!(f, "oracle request failed: {}", e),
+            OracleClientError::MissingField(field) => {
+                write!(f, "oracle response has no {} field", field)
+            }
+            OracleClientError::HexDecode(e) => write!(f, "invalid hex from oracle: {}", e),
+            OracleClientError::MessageDecode(e) => write!(f, "invalid message from oracle: {}", e),
+            OracleClientError::InvalidPublicKey(e) => {
+                write!(f, "invalid oracle public key: {}", e)
+            }
+            OracleClientError::PublicKeyMismatch { expected, actual } => write!(
+                f,
+                "oracle public key {} does not match the pinned public key {}",
+                actual, expected
+            ),
+            OracleClientError::EventIdMismatch { expected, actual } => write!(
+                f,
+                "oracle returned event {} instead of event {}",
+                actual, expected
+            ),
+            OracleClientError::InvalidAnnouncementSignature => {
+                "announcement is not signed by the oracle".fmt(f)
+            }
+            OracleClientError::NonceCountMismatch { expected, actual } => write!(
+                f,
+                "event descriptor requires {} nonces but the announcement has {}",
+                expected, actual
+            ),
+            OracleClientError::DuplicateNonce(index) => {
+                write!(f, "announcement reuses nonce {}", index)
+            }
+            OracleClientError::MissingAttestation(event_id) => {
+                write!(f, "missing attestation for event {}", event_id)
+            }
+            OracleClientError::OutcomeCountMismatch => {
+                "attestation does not have one outcome and signature per nonce".fmt(f)
+            }
+            OracleClientError::InvalidOutcome(index, outcome) => write!(
+                f,
+                "outcome {} at position {} is not a valid outcome of the event",
+                outcome, index
+            ),
+            OracleClientError::SignatureNonceMismatch(index) => {
+                write!(f, "signature {} does not use the announced nonce", index)
+            }
+            OracleClientError::InvalidAttestationSignature(index) => {
+                write!(f, "signature {} does not match its outcome", index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for OracleClientError {}
+
+impl From<DecodeHexError> for OracleClientError {
+    fn from(e: DecodeHexError) -> Self {
+        OracleClientError::HexDecode(e)
+    }
+}
+
+impl From<OracleClientError> for DlcManagerError {
+    fn from(e: OracleClientError) -> Self {
+        DlcManagerError::OracleError(e.to_string())
+    }
+}
+
+/// Reads the hex-encoded TLV message in `field` of `response`.
+fn decode_message<T: lightning::util::ser::Readable>(
+    response: &Value,
+    field: &'static str,
+) -> Result<T, OracleClientError> {
+    let encoded_hex = response[field]
+        .as_str()
+        .ok_or(OracleClientError::MissingField(field))?;
+    let buffer = decode_hex(encoded_hex)?;
+    T::read(&mut Cursor::new(buffer))
+        .map_err(|e| OracleClientError::MessageDecode(format!("{:?}", e)))
+}
+
+/// Returns the number of nonces required by `event_descriptor`.
+fn nb_nonces(event_descriptor: &EventDescriptor) -> usize {
+    match event_descriptor {
+        EventDescriptor::DigitDecompositionEvent(e) => {
+            usize::from(e.nb_digits) + usize::from(e.is_signed)
+        }
+        EventDescriptor::EnumEvent(_) => 1,
+    }
+}
+
+/// Returns whether `outcome` may be attested with the nonce at `index` of an event described by
+/// `event_descriptor`.
+fn is_valid_outcome(event_descriptor: &EventDescriptor, index: usize, outcome: &str) -> bool {
+    match event_descriptor {
+        EventDescriptor::DigitDecompositionEvent(e) => {
+            if e.is_signed && index == 0 {
+                return outcome == "+" || outcome == "-";
+            }
+            outcome.parse::<u16>().map_or(false, |digit| {
+                digit < e.base && digit.to_string() == outcome
+            })
+        }
+        EventDescriptor::EnumEvent(e) => e.outcomes.iter().any(|o| o == outcome),
+    }
+}
+
+/// Returns the message announcements of `oracle_event` are signed on, its tagged hash.
+fn announcement_message(oracle_event: &OracleEvent) -> Message {
+    let tag_hash = sha256::Hash::hash(ANNOUNCEMENT_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(&tag_hash[..]);
+    engine.input(&tag_hash[..]);
+    engine.input(&oracle_event.encode());
+    Message::from_slice(&sha256::Hash::from_engine(engine)[..]).expect("hashes are 32 bytes")
+}
+
+/// Verifies that `announcement` is the announcement of `event_id` signed by `public_key`, with
+/// one distinct nonce per outcome to attest.
+fn validate_announcement<C: Verification>(
+    secp: &Secp256k1<C>,
+    public_key: &PublicKey,
+    event_id: &str,
+    announcement: &OracleAnnouncement,
+) -> Result<(), OracleClientError> {
+    if announcement.oracle_public_key != *public_key {
+        return Err(OracleClientError::PublicKeyMismatch {
+            expected: *public_key,
+            actual: announcement.oracle_public_key,
+        });
+    }
+    if announcement.oracle_event.event_id != event_id {
+        return Err(OracleClientError::EventIdMismatch {
+            expected: event_id.to_string(),
+            actual: announcement.oracle_event.event_id.clone(),
+        });
+    }
+
+    let message = announcement_message(&announcement.oracle_event);
+    secp.schnorrsig_verify(&announcement.announcement_signature, &message, public_key)
+        .map_err(|_| OracleClientError::InvalidAnnouncementSignature)?;
+
+    let nonces = &announcement.oracle_event.oracle_nonces;
+    let expected = nb_nonces(&announcement.oracle_event.event_descriptor);
+    if nonces.len() != expected {
+        return Err(OracleClientError::NonceCountMismatch {
+            expected,
+            actual: nonces.len(),
+        });
+    }
+    for (index, nonce) in nonces.iter().enumerate() {
+        if nonces[..index].contains(nonce) {
+            return Err(OracleClientError::DuplicateNonce(index));
+        }
+    }
+    Ok(())
+}
+
+/// Verifies that `attestation` attests to valid outcomes of the event of `announcement`, which
+/// must already be validated, each signed with its announced nonce.
+fn validate_attestation<C: Verification>(
+    secp: &Secp256k1<C>,
+    announcement: &OracleAnnouncement,
+    attestation: &OracleAttestation,
+) -> Result<(), OracleClientError> {
+    if attestation.oracle_public_key != announcement.oracle_public_key {
+        return Err(OracleClientError::PublicKeyMismatch {
+            expected: announcement.oracle_public_key,
+            actual: attestation.oracle_public_key,
+        });
+    }
+    let nonces = &announcement.oracle_event.oracle_nonces;
+    if attestation.outcomes.len() != nonces.len() || attestation.signatures.len() != nonces.len() {
+        return Err(OracleClientError::OutcomeCountMismatch);
+    }
+
+    let event_descriptor = &announcement.oracle_event.event_descriptor;
+    for (index, ((outcome, signature), nonce)) in attestation
+        .outcomes
+        .iter()
+        .zip(&attestation.signatures)
+        .zip(nonces)
+        .enumerate()
+    {
+        if !is_valid_outcome(event_descriptor, index, outcome) {
+            return Err(OracleClientError::InvalidOutcome(index, outcome.clone()));
+        }
+        // the first half of a schnorr signature is its public nonce
+        if signature.as_ref()[..32] != nonce.serialize()[..] {
+            return Err(OracleClientError::SignatureNonceMismatch(index));
+        }
+        secp.schnorrsig_verify(
+            signature,
+            &Message::from_hashed_data::<sha256::Hash>(outcome.as_bytes()),
+            &attestation.oracle_public_key,
+        )
+        .map_err(|_| OracleClientError::InvalidAttestationSignature(index))?;
+    }
+    Ok(())
+}
+
 impl P2PDOracleClient {
     /// Try to create an instance of an oracle client connecting to the provided
-    /// host. Returns an error if the host could not be reached. Panics if the
-    /// oracle uses an incompatible format.
-    pub fn new(host: &str) -> Result<P2PDOracleClient, DlcManagerError> {
+    /// host. Returns an error if the host could not be reached, or if its public
+    /// key is not `pinned_public_key` when given.
+    pub fn new(
+        host: &str,
+        pinned_public_key: Option<PublicKey>,
+    ) -> Result<P2PDOracleClient, DlcManagerError> {
         if host.is_empty() {
             return Err(DlcManagerError::InvalidParameters(
                 "Invalid host".to_string(),
@@ -172,9 +436,34 @@ impl P2PDOracleClient {
         info!("Oracle Pub Key: {}", public_key.to_string());
 
         let public_key = PublicKey::from_str(&public_key)
-            .map_err(|_| DlcManagerError::OracleError("Oracle PubKey Error".to_string()))?;
+            .map_err(|e| OracleClientError::InvalidPublicKey(e.to_string()))?;
+        if let Some(pinned_public_key) = pinned_public_key {
+            if public_key != pinned_public_key {
+                return Err(OracleClientError::PublicKeyMismatch {
+                    expected: pinned_public_key,
+                    actual: public_key,
+                }
+                .into());
+            }
+        }
         info!("The p2pd oracle client has been created successfully");
-        Ok(P2PDOracleClient { host, public_key })
+        Ok(P2PDOracleClient {
+            host,
+            public_key,
+            secp: Secp256k1::verification_only(),
+        })
+    }
+
+    /// Fetches the oracle event `event_id` at `path` and returns its announcement once validated.
+    fn get_validated_announcement(
+        &self,
+        path: &str,
+        event_id: &str,
+    ) -> Result<(Value, OracleAnnouncement), OracleClientError> {
+        let v = get_json(path).map_err(|e| OracleClientError::Http(e.to_string()))?;
+        let announcement: OracleAnnouncement = decode_message(&v, "rust_announcement")?;
+        validate_announcement(&self.secp, &self.public_key, event_id, &announcement)?;
+        Ok((v, announcement))
     }
 }
 
@@ -221,48 +510,22 @@ impl Oracle for P2PDOracleClient {
         info!("Getting announcement for event_id {event_id}");
         let path = announcement_path(&self.host, event_id);
         info!("Getting announcement at URL {path}");
-        let v = get_json(&path)?;
-
-        let encoded_hex_announcement = v["rust_announcement"].as_str().unwrap(); //call to_string instead of as_str and watch your world crumble to pieces
-
-        let buffer = decode_hex(&encoded_hex_announcement).unwrap();
-
-        let mut announcement_cursor = Cursor::new(buffer);
-        let decoded_announcement =
-        <dlc_messages::oracle_msgs::OracleAnnouncement as lightning::util::ser::Readable>::read(
-            &mut announcement_cursor,
-        )
-        .unwrap();
-
-        Ok(decoded_announcement)
+        let (_, announcement) = self.get_validated_announcement(&path, event_id)?;
+        Ok(announcement)
     }
 
     fn get_attestation(&self, event_id: &str) -> Result<OracleAttestation, DlcManagerError> {
+        // the attestation is served along with its announcement, which is validated first
         let path = attestation_path(&self.host, event_id);
-        let v = get_json(&path)?;
-
-        //TODO: this next line might be None, throwing at unwrap, fix
-        let encoded_hex_attestation = match v["rust_attestation"].as_str() {
-            None => {
-                return Err(DlcManagerError::OracleError(format!(
-                    "missing attestation for event {}",
-                    event_id,
-                )))
-            }
-            Some(s) => s,
-        };
-
-        let buffer = decode_hex(&encoded_hex_attestation).unwrap();
-
-        let mut attestation_cursor = Cursor::new(buffer);
-        let decoded_attestation =
-            <dlc_messages::oracle_msgs::OracleAttestation as lightning::util::ser::Readable>::read(
-                &mut attestation_cursor,
-            )
-            .unwrap();
+        let (v, announcement) = self.get_validated_announcement(&path, event_id)?;
+        if v["rust_attestation"].is_null() {
+            return Err(OracleClientError::MissingAttestation(event_id.to_string()).into());
+        }
+        let attestation: OracleAttestation = decode_message(&v, "rust_attestation")?;
+        validate_attestation(&self.secp, &announcement, &attestation)?;
 
-        info!("GOT ATTESTATION as OBJECT! {:?}", decoded_attestation);
-        Ok(decoded_attestation)
+        info!("GOT ATTESTATION as OBJECT! {:?}", attestation);
+        Ok(attestation)
     }
 }
 
@@ -284,6 +547,74 @@ mod tests {
             assert_eq!(expected_date_time, date_time);
         }
     */
+    use dlc_messages::oracle_msgs::EnumEventDescriptor;
+    use secp256k1_zkp::schnorrsig::KeyPair;
+    use secp256k1_zkp::All;
+
+    fn signed_announcement(secp: &Secp256k1<All>, event_id: &str) -> OracleAnnouncement {
+        let keypair = KeyPair::from_seckey_slice(secp, &[1u8; 32]).unwrap();
+        let nonce_keypair = KeyPair::from_seckey_slice(secp, &[2u8; 32]).unwrap();
+        let oracle_event = OracleEvent {
+            oracle_nonces: vec![PublicKey::from_keypair(secp, &nonce_keypair)],
+            event_maturity_epoch: 1653865200,
+            event_descriptor: EventDescriptor::EnumEvent(EnumEventDescriptor {
+                outcomes: vec!["yes".to_string(), "no".to_string()],
+            }),
+            event_id: event_id.to_string(),
+        };
+        OracleAnnouncement {
+            announcement_signature: secp
+                .schnorrsig_sign_no_aux_rand(&announcement_message(&oracle_event), &keypair),
+            oracle_public_key: PublicKey::from_keypair(secp, &keypair),
+            oracle_event,
+        }
+    }
+
+    #[test]
+    fn validates_announcement() {
+        let secp = Secp256k1::new();
+        let announcement = signed_announcement(&secp, "event");
+        let public_key = announcement.oracle_public_key;
+        validate_announcement(&secp, &public_key, "event", &announcement).unwrap();
+
+        assert!(matches!(
+            validate_announcement(&secp, &public_key, "other", &announcement),
+            Err(OracleClientError::EventIdMismatch { .. })
+        ));
+
+        let other_keypair = KeyPair::from_seckey_slice(&secp, &[3u8; 32]).unwrap();
+        let other_public_key = PublicKey::from_keypair(&secp, &other_keypair);
+        assert!(matches!(
+            validate_announcement(&secp, &other_public_key, "event", &announcement),
+            Err(OracleClientError::PublicKeyMismatch { .. })
+        ));
+
+        let mut tampered = announcement;
+        tampered.oracle_event.event_maturity_epoch += 1;
+        assert!(matches!(
+            validate_announcement(&secp, &public_key, "event", &tampered),
+            Err(OracleClientError::InvalidAnnouncementSignature)
+        ));
+    }
+
+    #[test]
+    fn rejects_undecodable_announcement() {
+        let secp = Secp256k1::new();
+        let public_key = signed_announcement(&secp, "event").oracle_public_key;
+        let client = P2PDOracleClient {
+            host: format!("{}/", mockito::server_url()),
+            public_key,
+            secp: Secp256k1::verification_only(),
+        };
+        let path: &str = &announcement_path("/", "event");
+        let _m = mock("GET", path)
+            .with_body(r#"{"rust_announcement":"fdd824"}"#)
+            .create();
+
+        assert!(client.get_announcement("event").is_err());
+        assert!(client.get_attestation("event").is_err());
+    }
+
     fn pubkey_mock() -> Mock {
         let path: &str = &pubkey_path("/");
         mock("GET", path).with_body(
@@ -301,7 +632,7 @@ mod tests {
                 .parse()
                 .unwrap();
 
-        let client = P2PDOracleClient::new(url).expect("Error creating client instance.");
+        let client = P2PDOracleClient::new(url, None).expect("Error creating client instance.");
 
         assert_eq!(expected_pk, client.get_public_key());
     }
@@ -314,7 +645,7 @@ mod tests {
         let path: &str = &announcement_path("/", "2021-06-29T05:10:00Z");
         let _m = mock("GET", path).with_body(r#"{"announcementSignature":"f83db0ca25e4c209b55156737b0c65470a9702fe9d1d19a129994786384289397895e403ff37710095a04a0841a95738e3e8bc35bdef6bce50bf34eeb182bd9b","oraclePublicKey":"10dc8cf51ae3ee1c7967ffb9c9633a5ab06206535d8e1319f005a01ba33bc05d","oracleEvent":{"oracleNonces":["aca32fc8dead13983c655638ef921f1d38ef2f5286e58b2a1dab32b6e086e208","89603f8179830590fdce45eb17ba8bdf74e295a4633b58b46c9ede8274774164","5f3fcdfbba9ec75cb0868e04ec1f97089b4153fb2076bd1e017048e9df633aa1","8436d00f7331491dc6512e560a1f2414be42e893992eccb495642eefc7c5bf37","0d2593764c9c27eba0be3ca6c71a2de4e49a5f4aa1ce1e2cc379be3939547501","414318491e96919e67583db7a47eb1f8b4f1194bcb5b5dcc4fd10492d89926e4","b9a5ded7295e0343f385e5abedfd9e5f4137de8f67de0afa9396f7e0f996ef79","badf0bfe230ed605161630d8e3a092d7448461042db38912bc6c6a0ab195ff71","6e4780213cd7ed9de1300146079b897cae89dec7800065f615974193f58aa6db","7b12b48ad95634ee4ca476dd57e634fddc328e10276e71d27e0ae626fad7d699","a8058604adf590a1c38f8be19aa44175eb2d1130eb4d7f39a34f89f0a3fbed27","ffc3208f60b585cdc778be1290b352c34c22652d5348a87885816bcf17a80116","cb34c13f80b49e729e863035f30e1f8ea7777618eedb6d666c3b1c85a5b8a637","5000991f4631c0bba5d026f02125fdbe77e019dde57d31ce7f23ae3601a18623","094433a2432b81bbb6d6b7d65dc3498e2a7c9de5f35672d67097d54d920eadd2","11dff6b40b0938e1943c7888633d88871c2a2a1c16f412b22b80ba7ed8af8788","d5957f1a199b4abbc06894479c722ad0c4f120f0d5afeb76d589127213e33170","80e09bb453e6a0a444ec3ba222a62ecd59540b9dd8280566a17bebdfdfbd7a9e","0fe775b79b2172cb961e7c1aa54d521360903680680aaa55ea8be0404ee3768c","bfcdbb2cbcffba41048149d4bcf2a41cd5fd0a713df6f48104ade3022c284575"],"eventMaturityEpoch":1653865200,"eventDescriptor":{"digitDecompositionEvent":{"base":2,"isSigned":false,"unit":"usd/btc","precision":0,"nbDigits":20}},"eventId":"btcusd1653865200"}}"#).create();
 
-        let client = P2PDOracleClient::new(url).expect("Error creating client instance");
+        let client = P2PDOracleClient::new(url, None).expect("Error creating client instance");
 
         client
             .get_announcement("btcusd1624943400")
@@ -330,7 +661,7 @@ mod tests {
 
         let _m = mock("GET", path).with_body(r#"{"eventId":"btcusd1653517020","signatures":["ee05b1211d5f974732b10107dd302da062be47cd18f061c5080a50743412f9fd590cad90cfea762472e6fe865c4223bd388c877b7881a27892e15843ff1ac360","59ab83597089b48f5b3c2fd07c11edffa6b1180bdb6d9e7d6924979292d9c53fe79396ceb0782d5941c284d1642377136c06b2d9c2b85bda5969a773a971b5b0","d1f8c31a83bb34433da5b9808bb3692dd212b9022b7bc8f269fc817e96a7195db18262e934bebd4e68a3f2c96550826a5530350662df4c86c004f5cf1121ca67","e5cec554c39c4dd544d70175128271eecad77c1e3eaa6994c657e257d5c1c9dcd19b041ea8030e75448245b7f91705ad914c32761671a6172f928904b439ea6b","a209116d20f0931113c0880e8cd22d3f003609a32322ff8df241ef16e7d4efd1a9b723f582a22073e21188635f09f41f270f3126014542861be14b62b09c0ecc","f1da0b482f08f545a92338392b71cec33d948a5e5732ee4d5c0a87bd6b6cc12feeb1498da7afd93ae48ec4ce581ee79c0e92f338d3777c2ef06578e4ec1a853c","d9ab68244a3b47cc8cbd5a972f2f5059fc6b9711dba8d4a7a23607a99b9655593bab3abc1d3b02402cd0809c3c7016c741742efb363227de2bcfdcf290a053b3","c1146c1767a947f77794d05a2f58e50af824e3c8d70adde883e58d2dc1ddb157323b0aaf8cfb5b076a12395756bdcda64ab5d4799e43c88a41993659e6d49471","0d29d9383c9ee41055e1cb40104c9ca75280162779c0162cb6bf9aca2b223aba17de4b3f0f29ae6b749f22ba467b7e9f05456e8abb3ec328f62b7a924c6d4828","2bcc54002ceb271a940f24bc6dd0562b99c2d76cfb8f145f42ac37bc34fd3e94adba1194c5be91932b818c5715c73f287e066e228d796a373c4aec67fd777070","a91f77e3435c577682ff744d6f7da66c865a42e8645276dedf2ed2b8bc4c80285dff4b553b2231592e0fa8b4f242acb6888519fe82c457cc5204e5d9d511303a","546409d6bcdcfd5bef39957c8b1b09f7805b08ec2311bc73cf6927ae11f3567ffe8428aa7faa661518e9c02a702212ab05e494aab84624c3dd1a710f8c4c369b","9d601ee8a3d28dcdfdd05581f1b24d6e5a576f0b5544eb7c9921cb87a23fdb293c1edca89b43b5b84c1e305fbe52facbe6b03575aed8f95b4faccc90e0eb45ef","636b8028e9cd6cba6be5b3c1789b62aecfc17e9c28d7a621cfad2c3cf751046528028e1dbd6cee050d5d570cf5a3d8986471d73e7edca4093e36fc8e1097fb65","57c6337b52dc7fd8f49b29105f168fc9b4cb88ed2ba5f0e9a80a21e20836f87f875c3fe92afb437dd5647630b54eda6ba1be76ba6df8b641eb2e8be8ff1182dc","9e8843e32f9de4cd6d5bb9e938fd014babe11bb1faf35fc411d754259bc374f34dd841ed91f6bb3f030bc55a4791cdc41471c33b3f05fd35b9d1768fd381f953","97da4963747ab5e50534b93274065cba4fd24e6b7a9d3310db2596af24f70961fb03535e2a5ae272f7ea14e86daafa57073631596fecf7ceadf4ae3e6941b69e","94a414569743f87f1462a503be8cff1f229096d190b8b1349519c612b74eea872d5d763570aaaa54fad0605a43d742203bce489deea5570750030191e293c253","4d7117b89aad73eca7b341749bd54ffdd459b9b8b4ff128344d09273f66a3d2c01d2c86b61f7642d6e81f488580b456685cd68660458cff83b8858a05c9a1f4d","b12153a393a4fddac3079c1878cb89afccfe0ac8f539743c0608049f445e49ac7c89e33fcf832cda8d7e8a4f4dae94a303170f16c697feed8b78015873bd5ffc"],"values":["0","0","0","0","0","1","1","1","0","1","0","0","0","0","1","1","1","0","1","0"]}"#).create();
 
-        let client = P2PDOracleClient::new(url).expect("Error creating client instance");
+        let client = P2PDOracleClient::new(url, None).expect("Error creating client instance");
 
         client
             .get_attestation("btcusd1624943400")